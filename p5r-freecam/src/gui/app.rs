@@ -14,7 +14,7 @@ use std::{
     ops::{ Deref, DerefMut },
     path::PathBuf,
     ptr::NonNull,
-    sync::{ Mutex, OnceLock },
+    sync::{ Mutex, OnceLock, atomic::{ AtomicBool, Ordering } },
     thread::sleep,
     time::Duration
 };
@@ -42,7 +42,7 @@ use windows::{
     }
 };
 use windows::Win32::Foundation::RECT;
-use windows::Win32::UI::WindowsAndMessaging::GetClientRect;
+use windows::Win32::UI::WindowsAndMessaging::{GetClientRect, GetWindowRect};
 use crate::state::camera::Freecam;
 
 #[derive(Debug)]
@@ -227,12 +227,22 @@ pub(crate) static IMGUI_GLB: OnceLock<StatePointer<ImContext>> = OnceLock::new()
 pub(crate) static RENDERER_GLB: OnceLock<StatePointer<D3D11State>> = OnceLock::new();
 pub(crate) static WINDOW_GLB: OnceLock<StatePointer<Window>> = OnceLock::new();
 pub(crate) static IMPLOT_GLB: OnceLock<StatePointer<ImPlotCtx>> = OnceLock::new();
+// set when the user finishes moving or resizing the window, so the session only reads the rect then
+pub(crate) static WINDOW_MOVED: AtomicBool = AtomicBool::new(false);
 
-pub fn init_debug_window() -> Result<(), Box<dyn Error>> {
+pub fn init_debug_window(rect: Option<[i32; 4]>) -> Result<(), Box<dyn Error>> {
     let proc = ProcessInfo::get_current_process().unwrap();
     let main_icon = unsafe { std::mem::transmute::<usize, PCSTR>(0x69) }; // icon ID defined in CreateWindow
     let main_icon = unsafe { LoadIconA(Some(proc.get_main_module().as_raw().into()), main_icon).ok() };
-    // for now: always open in Display 1 at half resolution, near the top left
+    // reuse the layout from the last session if there is one
+    if let Some([left, top, right, bottom]) = rect {
+        if right > left && bottom > top {
+            let win_pos = Vec2::new(left as f32, top as f32);
+            let win_size = Vec2::new((right - left) as f32, (bottom - top) as f32);
+            return App::show_window(win_pos, win_size, main_icon);
+        }
+    }
+    // otherwise open in Display 1 at half resolution, near the top left
     let mut main_disp_res = Vec2::new(unsafe { GetSystemMetrics(SM_CXSCREEN) } as f32, unsafe { GetSystemMetrics(SM_CYSCREEN) } as f32 );
     let win_pos = Vec2::new(50., 50.);
     // create a square equal to 1/2 of height
@@ -256,4 +266,16 @@ pub fn shutdown_debug_window() {
 
 pub fn get_hwnd() -> HWND {
     WINDOW_GLB.get().unwrap().get_handle()
+}
+
+pub fn take_window_moved() -> bool {
+    WINDOW_MOVED.swap(false, Ordering::Relaxed)
+}
+
+pub fn get_window_rect() -> Option<[i32; 4]> {
+    let window = WINDOW_GLB.get()?;
+    let mut rect: MaybeUninit<RECT> = MaybeUninit::uninit();
+    unsafe { GetWindowRect(window.get_handle(), rect.as_mut_ptr()).ok()?; }
+    let rect = unsafe { rect.assume_init() };
+    Some([rect.left, rect.top, rect.right, rect.bottom])
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ use crate::gui::app::AppGlobalFlags;
 use glam::Vec2;
 use imgui::{
     BackendFlags,
+    ClipboardBackend,
     Context as ImContext,
     // FotnCon
     // FontSource,
@@ -16,11 +17,16 @@ use riri_mod_tools_rt::logln;
 use windows::{
     core::{ BOOL, PCWSTR },
     Win32::{
-        Foundation::{ HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM },
+        Foundation::{ HANDLE, HGLOBAL, HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM },
         Graphics::Gdi::ScreenToClient,
-        System::LibraryLoader::GetModuleHandleA,
+        System::{
+            DataExchange::{ CloseClipboard, EmptyClipboard, GetClipboardData, OpenClipboard, SetClipboardData },
+            LibraryLoader::GetModuleHandleA,
+            Memory::{ GlobalAlloc, GlobalFree, GlobalLock, GlobalUnlock, GMEM_MOVEABLE },
+            Ole::CF_UNICODETEXT
+        },
         UI::{
-            Input::KeyboardAndMouse::VIRTUAL_KEY,
+            Input::KeyboardAndMouse::{ GetKeyState, VIRTUAL_KEY, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT },
             WindowsAndMessaging::{
                 CreateWindowExW,
                 DefWindowProcW,
@@ -39,6 +45,7 @@ use windows::{
                 WHEEL_DELTA,
                 WM_CHAR,
                 WM_CLOSE,
+                WM_EXITSIZEMOVE,
                 WM_MOUSEMOVE,
                 WM_NCMOUSEMOVE,
                 WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
@@ -153,6 +160,68 @@ fn lparam_get_high_word(lparam: LPARAM) -> i16 {
     ((lparam.0 >> u16::BITS as isize) & u16::MAX as isize) as i16
 }
 
+// Clipboard access for text widgets and copy/paste in the GUI
+#[derive(Debug)]
+pub struct Win32Clipboard {
+    hwnd: HWND,
+}
+
+impl Win32Clipboard {
+    pub fn new(hwnd: HWND) -> Self {
+        Self { hwnd }
+    }
+}
+
+impl ClipboardBackend for Win32Clipboard {
+    fn get(&mut self) -> Option<String> {
+        unsafe {
+            OpenClipboard(Some(self.hwnd)).ok()?;
+            let text = match GetClipboardData(CF_UNICODETEXT.0 as u32) {
+                Ok(handle) => {
+                    let hglobal = HGLOBAL(handle.0);
+                    let data = GlobalLock(hglobal) as *const u16;
+                    if data.is_null() { None } else {
+                        let len = (0..).take_while(|i| *data.add(*i) != 0).count();
+                        let text = String::from_utf16_lossy(std::slice::from_raw_parts(data, len));
+                        let _ = GlobalUnlock(hglobal);
+                        Some(text)
+                    }
+                },
+                Err(_) => None
+            };
+            let _ = CloseClipboard();
+            text
+        }
+    }
+
+    fn set(&mut self, value: &str) {
+        let wide: Vec<u16> = value.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            if OpenClipboard(Some(self.hwnd)).is_err() { return; }
+            if EmptyClipboard().is_ok() {
+                if let Ok(hglobal) = GlobalAlloc(GMEM_MOVEABLE, wide.len() * size_of::<u16>()) {
+                    let data = GlobalLock(hglobal) as *mut u16;
+                    // the clipboard only takes ownership of the memory if SetClipboardData succeeds
+                    let owned = !data.is_null() && {
+                        std::ptr::copy_nonoverlapping(wide.as_ptr(), data, wide.len());
+                        // GlobalUnlock reports an error once the lock count hits zero, so ignore it
+                        let _ = GlobalUnlock(hglobal);
+                        match SetClipboardData(CF_UNICODETEXT.0 as u32, Some(HANDLE(hglobal.0))) {
+                            Ok(_) => true,
+                            Err(e) => {
+                                logln!(Verbose, "Couldn't set clipboard data: {}", e);
+                                false
+                            }
+                        }
+                    };
+                    if !owned { let _ = GlobalFree(Some(hglobal)); }
+                }
+            }
+            let _ = CloseClipboard();
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Win32Impl {
     last_frame: Instant,
@@ -166,6 +235,7 @@ impl Win32Impl {
     pub fn new(ctx: &mut ImContext, hwnd: HWND) -> Self {
         let platform_name = format!("riri-imgui-hook-win32");
         ctx.set_platform_name(Some(platform_name));
+        ctx.set_clipboard_backend(Win32Clipboard::new(hwnd));
         let io = ctx.io_mut();
         io.backend_flags.insert(BackendFlags::HAS_MOUSE_CURSORS);
         Self { last_frame: Instant::now(), hwnd }
@@ -203,6 +273,18 @@ impl Win32Impl {
         }
     }
 
+    fn is_vk_down(vk: VIRTUAL_KEY) -> bool {
+        unsafe { GetKeyState(vk.0 as i32) as u16 & 0x8000 != 0 }
+    }
+
+    fn update_key_modifiers(&mut self, ctx: &mut ImContext) {
+        let io = ctx.io_mut();
+        io.add_key_event(Key::ModCtrl, Self::is_vk_down(VK_CONTROL));
+        io.add_key_event(Key::ModShift, Self::is_vk_down(VK_SHIFT));
+        io.add_key_event(Key::ModAlt, Self::is_vk_down(VK_MENU));
+        io.add_key_event(Key::ModSuper, Self::is_vk_down(VK_LWIN) || Self::is_vk_down(VK_RWIN));
+    }
+
     // Map VK_xxx to ImGuiKey_xxx.
     fn from_key_event(wparam: WPARAM, lparam: LPARAM) -> Option<Key> {
         // There is no distinct VK_xxx for keypad enter, instead it is VK_RETURN + KF_EXTENDED.
@@ -330,6 +412,8 @@ impl Win32Impl {
         } else if msg == WM_CLOSE {
             (*crate::gui::app::APP_GLB.lock().unwrap()).flags |= AppGlobalFlags::WINDOW_CLOSED;
             return LRESULT(0);
+        } else if msg == WM_EXITSIZEMOVE {
+            crate::gui::app::WINDOW_MOVED.store(true, std::sync::atomic::Ordering::Relaxed);
         }
         if let Some(p) = crate::gui::app::PLATFORM_GLB.get() {
             let platform = unsafe { &mut *p.as_ptr() };
@@ -421,7 +505,7 @@ impl Win32Impl {
             WM_KEYDOWN | WM_KEYUP | WM_SYSKEYDOWN | WM_SYSKEYUP => {
                 let is_key_down = umsg == WM_KEYDOWN || umsg == WM_SYSKEYDOWN;
                 if wparam.0 < 256 {
-                    // self.update_key_modifiers(ctx);
+                    self.update_key_modifiers(ctx);
                     if let Some(key) = Self::from_key_event(wparam, lparam) {
                         let io = ctx.io_mut();
                         io.add_key_event(key, is_key_down);
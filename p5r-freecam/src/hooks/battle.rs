@@ -6,29 +6,48 @@ use opengfd::kernel::{
 use riri_mod_tools_proc::{ create_hook, riri_hook_fn, riri_mods_loaded_fn };
 use riri_mod_tools_rt::logln;
 use xrd744_lib::btl::camera::CameraController;
+use xrd744_lib::btl::frequency::Frequency;
 
 #[riri_hook_fn(user_defined())]
 pub unsafe extern "C" fn btl_camera_update(p_this: *mut u8, p_pkg: *mut u8, delta: f32) {
     match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
         Some(task) => {
             let ctx = task.get_main_work_mut().unwrap();
-            if ctx.flags.contains(FreecamFlags::ACTIVE) {
-                let this = &mut *(p_this as *mut CameraController<GfdAllocator>);
+            let this = &mut *(p_this as *mut CameraController<GfdAllocator>);
+            let view = this.get_camera_type();
+            ctx.battle_view.current = Some(view);
+            if ctx.flags.contains(FreecamFlags::ACTIVE) && ctx.battle_view.settings.is_overridden(view) {
                 if let Some(cam) = this.get_camera_mut().get_entity_mut() {
                     cam.set_view_transform(ctx.update_view_matrix());
-                    cam.set_roll(ctx.roll);
+                    cam.set_roll(ctx.get_view_roll());
+                    ctx.apply_clip_planes(cam);
                 }
+                ctx.apply_battle_clip_planes(this);
             } else {
+                if let Some(cam) = this.get_camera_mut().get_entity_mut() { ctx.restore_clip_planes(cam); }
+                ctx.restore_battle_clip_planes(this);
                 original_function!(p_this, p_pkg, delta)
             }
         },
         None => original_function!(p_this, p_pkg, delta)
     }
 }
+// freeze on the exact update that finishes a frame step
+#[riri_hook_fn(user_defined())]
+pub unsafe extern "C" fn btl_frequency_update(p_this: *mut u8, delta: f32) {
+    original_function!(p_this, delta);
+    if crate::state::timing::tick_frame_step() && Freecam::check_active() {
+        (&mut *(p_this as *mut Frequency)).set_time(0.);
+    }
+}
 #[riri_mods_loaded_fn]
 fn setup_battle_hooks() {
     let usually_vtable = riri_mod_tools_rt::vtable::get_vtable("Usually@camera@btl@@") as *const usize;
     let usually_tick = *usually_vtable.add(2);
     logln!(Information, "Got btl::camera::Usually::update at 0x{:x}", usually_tick);
     create_hook!(usually_tick, btl_camera_update);
+    let frequency_vtable = riri_mod_tools_rt::vtable::get_vtable("Frequency@btl@@") as *const usize;
+    let frequency_tick = *frequency_vtable.add(2);
+    logln!(Information, "Got btl::Frequency::update at 0x{:x}", frequency_tick);
+    create_hook!(frequency_tick, btl_frequency_update);
 }
\ No newline at end of file
@@ -1,10 +1,11 @@
 use crate::state::camera::{Freecam, FreecamFlags};
+use crate::state::event_command::EvtCommandInfo;
 use crate::state::node::FreecamNode;
 use opengfd::kernel::{
     allocator::GfdAllocator,
     task::{ Task as GfdTask, UpdateTask }
 };
-use riri_mod_tools_proc::{create_hook, riri_hook_fn, riri_hook_static, riri_static};
+use riri_mod_tools_proc::{riri_hook_fn, riri_hook_static, riri_static};
 use riri_mod_tools_rt::{ logln, sigscan_resolver };
 use std::ptr::NonNull;
 use glam::{EulerRot, Quat, Vec3A, Vec4};
@@ -17,6 +18,7 @@ pub unsafe extern "C" fn set_event_command_name(ofs: usize) -> Option<NonNull<u8
         Some(v) => v, None => return None
     };
     let get_cmd_table = addr.as_ptr() as *mut *mut u8;
+    let mut commands = Vec::with_capacity(EVT_COMMAND_COUNT);
     for i in 0..EVT_COMMAND_COUNT {
         let table_addr = sigscan_resolver::get_indirect_address_long_abs(*get_cmd_table.add(i));
         match table_addr {
@@ -26,35 +28,21 @@ pub unsafe extern "C" fn set_event_command_name(ofs: usize) -> Option<NonNull<u8
                 let ptr = match riri_mod_tools_rt::sigscan_resolver::get_address_may_thunk_absolute(ptr) {
                     Some(v) => unsafe { v.as_ptr() as usize }, None => 0
                 };
-                match table.get_name() {
-                    "CMD_" => {
-                        logln!(Verbose, "Created hook for event command CAMERA MOVE DIRECT: 0x{:x}", ptr);
-                        create_hook!(ptr, camera_move_direct_func_1_0);
-                    },
-                    "CSA_" => {
-                        logln!(Verbose, "Created hook for event command CAMERA SET ASSET: 0x{:x}", ptr);
-                        create_hook!(ptr, camera_set_asset_func_1_0);
-                    },
-                    "CSD_" => {
-                        logln!(Verbose, "Created hook for event command CAMERA SET DIRECT: 0x{:x}", ptr);
-                        create_hook!(ptr, camera_set_direct_func_1_0);
-                    },
-                    "CShk" => {
-                        logln!(Verbose, "Created hook for event command CAMERA SHAKE: 0x{:x}", ptr);
-                        create_hook!(ptr, camera_shake_func_1_0);
-                    },
-                    "CSF_" => {
-                        logln!(Verbose, "Created hook for event command CAMERA SET FIELD: 0x{:x}", ptr);
-                        create_hook!(ptr, camera_set_field_func_1_0);
-                    },
-                    _ => ()
-                }
+                commands.push(EvtCommandInfo {
+                    name: table.get_name().to_owned(),
+                    execute: ptr,
+                    struct_size: table.get_execute_table().get_struct_size(),
+                    slot: None
+                });
             },
             None => {
                 logln!(Error, "TABLE FAILED: Entry {}", i);
+                // keep indices lined up with the hooks in event_command.rs
+                commands.push(EvtCommandInfo { name: format!("#{}", i), execute: 0, struct_size: 0, slot: None });
             }
         }
     }
+    crate::state::event_command::set_evt_commands(commands);
     logln!(Information, "got evtCommandTable: 0x{:x}", addr.as_ptr() as usize);
     Some(addr)
 }
@@ -68,81 +56,6 @@ pub unsafe extern "C" fn set_event_command_name(ofs: usize) -> Option<NonNull<u8
 ))]
 riri_static!(EVENT_COMMAND_TYPES_HOOK, usize);
 
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn camera_move_direct_func_1_0(p_work: *mut u8) -> bool {
-    logln!(Verbose, "evtCommandRequest: CAMERA MOVE DIRECT");
-    match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
-        Some(t) => {
-            let work = t.get_main_work_mut().unwrap();
-            match work.flags.contains(FreecamFlags::ACTIVE) {
-                true => true,
-                false => original_function!(p_work)
-            }
-        },
-        None => original_function!(p_work)
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn camera_set_asset_func_1_0(p_work: *mut u8) -> bool {
-    logln!(Verbose, "evtCommandRequest: CAMERA SET ASSET");
-    match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
-        Some(t) => {
-            let work = t.get_main_work_mut().unwrap();
-            match work.flags.contains(FreecamFlags::ACTIVE) {
-                true => true,
-                false => original_function!(p_work)
-            }
-        },
-        None => original_function!(p_work)
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn camera_set_direct_func_1_0(p_work: *mut u8) -> bool {
-    logln!(Verbose, "evtCommandRequest: CAMERA SET DIRECT");
-    match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
-        Some(t) => {
-            let work = t.get_main_work_mut().unwrap();
-            match work.flags.contains(FreecamFlags::ACTIVE) {
-                true => true,
-                false => original_function!(p_work)
-            }
-        },
-        None => original_function!(p_work)
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn camera_shake_func_1_0(p_work: *mut u8) -> bool {
-    logln!(Verbose, "evtCommandRequest: CAMERA SHAKE");
-    match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
-        Some(t) => {
-            let work = t.get_main_work_mut().unwrap();
-            match work.flags.contains(FreecamFlags::ACTIVE) {
-                true => true,
-                false => original_function!(p_work)
-            }
-        },
-        None => original_function!(p_work)
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn camera_set_field_func_1_0(p_work: *mut u8) -> bool {
-    logln!(Verbose, "evtCommandRequest: CAMERA SET FIELD");
-    match GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
-        Some(t) => {
-            let work = t.get_main_work_mut().unwrap();
-            match work.flags.contains(FreecamFlags::ACTIVE) {
-                true => true,
-                false => original_function!(p_work)
-            }
-        },
-        None => original_function!(p_work)
-    }
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn set_evt_state_loop(ofs: usize) -> Option<NonNull<u8>> {
     let addr = match sigscan_resolver::get_address_may_thunk(ofs) {
@@ -160,21 +73,27 @@ pub unsafe extern "C" fn set_evt_state_loop(ofs: usize) -> Option<NonNull<u8>> {
 ))]
 #[allow(non_snake_case)]
 pub unsafe extern "C" fn EvtStateLoop(p_task: *mut u8, delta: f32) -> u64 {
-    let _ = evt_state_loop_inner(&mut *(p_task as *mut GfdTask<GfdAllocator, EvtTask>));
+    let _ = evt_state_loop_inner(&mut *(p_task as *mut GfdTask<GfdAllocator, EvtTask>), delta);
     original_function!(p_task, delta)
 }
 
 type VoidRes = Result<(), ()>;
 
-fn evt_state_loop_inner(task: &mut GfdTask<GfdAllocator, EvtTask>) -> VoidRes {
+fn evt_state_loop_inner(task: &mut GfdTask<GfdAllocator, EvtTask>, delta: f32) -> VoidRes {
     let work = task.get_main_work_mut().ok_or(())?;
+    let frame = work.get_current_frame();
+    crate::state::event_trace::set_evt_frame(frame);
     let ec = work.get_ctrl_mut().ok_or(())?;
     let cam = ec.get_camera_mut().ok_or(())?;
     if let Some(free) = GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
         let ctx = free.get_main_work_mut().unwrap();
+        ctx.capture_event_camera(cam, frame, delta);
         if ctx.flags.contains(FreecamFlags::ACTIVE) {
             cam.set_view_transform(ctx.update_view_matrix());
-            cam.set_roll(ctx.roll);
+            cam.set_roll(ctx.get_view_roll());
+            ctx.apply_clip_planes(cam);
+        } else {
+            ctx.restore_clip_planes(cam);
         }
     }
     Ok(())
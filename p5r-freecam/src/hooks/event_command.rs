@@ -0,0 +1,62 @@
+use riri_mod_tools_proc::{create_hook, riri_hook_fn};
+use riri_mod_tools_rt::logln;
+use crate::state::capture::note_evt_command;
+use crate::state::event_command::is_evt_slot_suppressed;
+use crate::state::event_trace::trace_evt_command;
+
+// one hook per distinct execute function so each keeps its own original function
+macro_rules! evt_command_hooks {
+    ($($slot:literal => $name:ident),* $(,)?) => {
+        pub(crate) fn hook_evt_command(slot: usize, execute: usize) {
+            match slot {
+                $($slot => create_hook!(execute, $name),)*
+                _ => logln!(Verbose, "Invalid event command hook slot {}", slot)
+            }
+        }
+        $(
+            #[riri_hook_fn(user_defined())]
+            pub unsafe extern "C" fn $name(p_work: *mut u8) -> bool {
+                trace_evt_command($slot, p_work);
+                note_evt_command($slot);
+                match is_evt_slot_suppressed($slot) {
+                    true => true,
+                    false => original_function!(p_work)
+                }
+            }
+        )*
+    };
+}
+
+evt_command_hooks! {
+    0 => evt_command_execute_0, 1 => evt_command_execute_1, 2 => evt_command_execute_2, 3 => evt_command_execute_3,
+    4 => evt_command_execute_4, 5 => evt_command_execute_5, 6 => evt_command_execute_6, 7 => evt_command_execute_7,
+    8 => evt_command_execute_8, 9 => evt_command_execute_9, 10 => evt_command_execute_10, 11 => evt_command_execute_11,
+    12 => evt_command_execute_12, 13 => evt_command_execute_13, 14 => evt_command_execute_14, 15 => evt_command_execute_15,
+    16 => evt_command_execute_16, 17 => evt_command_execute_17, 18 => evt_command_execute_18, 19 => evt_command_execute_19,
+    20 => evt_command_execute_20, 21 => evt_command_execute_21, 22 => evt_command_execute_22, 23 => evt_command_execute_23,
+    24 => evt_command_execute_24, 25 => evt_command_execute_25, 26 => evt_command_execute_26, 27 => evt_command_execute_27,
+    28 => evt_command_execute_28, 29 => evt_command_execute_29, 30 => evt_command_execute_30, 31 => evt_command_execute_31,
+    32 => evt_command_execute_32, 33 => evt_command_execute_33, 34 => evt_command_execute_34, 35 => evt_command_execute_35,
+    36 => evt_command_execute_36, 37 => evt_command_execute_37, 38 => evt_command_execute_38, 39 => evt_command_execute_39,
+    40 => evt_command_execute_40, 41 => evt_command_execute_41, 42 => evt_command_execute_42, 43 => evt_command_execute_43,
+    44 => evt_command_execute_44, 45 => evt_command_execute_45, 46 => evt_command_execute_46, 47 => evt_command_execute_47,
+    48 => evt_command_execute_48, 49 => evt_command_execute_49, 50 => evt_command_execute_50, 51 => evt_command_execute_51,
+    52 => evt_command_execute_52, 53 => evt_command_execute_53, 54 => evt_command_execute_54, 55 => evt_command_execute_55,
+    56 => evt_command_execute_56, 57 => evt_command_execute_57, 58 => evt_command_execute_58, 59 => evt_command_execute_59,
+    60 => evt_command_execute_60, 61 => evt_command_execute_61, 62 => evt_command_execute_62, 63 => evt_command_execute_63,
+    64 => evt_command_execute_64, 65 => evt_command_execute_65, 66 => evt_command_execute_66, 67 => evt_command_execute_67,
+    68 => evt_command_execute_68, 69 => evt_command_execute_69, 70 => evt_command_execute_70, 71 => evt_command_execute_71,
+    72 => evt_command_execute_72, 73 => evt_command_execute_73, 74 => evt_command_execute_74, 75 => evt_command_execute_75,
+    76 => evt_command_execute_76, 77 => evt_command_execute_77, 78 => evt_command_execute_78, 79 => evt_command_execute_79,
+    80 => evt_command_execute_80, 81 => evt_command_execute_81, 82 => evt_command_execute_82, 83 => evt_command_execute_83,
+    84 => evt_command_execute_84, 85 => evt_command_execute_85, 86 => evt_command_execute_86, 87 => evt_command_execute_87,
+    88 => evt_command_execute_88, 89 => evt_command_execute_89, 90 => evt_command_execute_90, 91 => evt_command_execute_91,
+    92 => evt_command_execute_92, 93 => evt_command_execute_93, 94 => evt_command_execute_94, 95 => evt_command_execute_95,
+    96 => evt_command_execute_96, 97 => evt_command_execute_97, 98 => evt_command_execute_98, 99 => evt_command_execute_99,
+    100 => evt_command_execute_100, 101 => evt_command_execute_101, 102 => evt_command_execute_102, 103 => evt_command_execute_103,
+    104 => evt_command_execute_104, 105 => evt_command_execute_105, 106 => evt_command_execute_106, 107 => evt_command_execute_107,
+    108 => evt_command_execute_108, 109 => evt_command_execute_109, 110 => evt_command_execute_110, 111 => evt_command_execute_111,
+    112 => evt_command_execute_112, 113 => evt_command_execute_113, 114 => evt_command_execute_114, 115 => evt_command_execute_115,
+    116 => evt_command_execute_116, 117 => evt_command_execute_117, 118 => evt_command_execute_118, 119 => evt_command_execute_119,
+    120 => evt_command_execute_120,
+}
@@ -1,18 +1,9 @@
-use std::{
-    ptr::NonNull,
-    sync::OnceLock
-};
-use opengfd::kernel::{
-    allocator::GfdAllocator,
-    task::{
-        Task as GfdTask,
-        UpdateTask
-    }
-};
+use std::ptr::NonNull;
 use opengfd::kernel::task::TaskFunctionReturn;
 use riri_mod_tools_proc::{ create_hook, riri_hook_fn, riri_hook_static, riri_static };
 use riri_mod_tools_rt::{logln, sigscan_resolver};
-use crate::state::camera::{Freecam, FreecamFlags};
+use crate::state::camera::Freecam;
+use crate::state::hud::is_hud_slot_hidden;
 use xrd744_lib::fld::proc::ProcTable;
 
 #[no_mangle]
@@ -114,129 +105,41 @@ pub unsafe extern "C" fn setfldProcTable(ofs: usize) -> Option<NonNull<u8>> {
 ))]
 riri_static!(FLD_PROC_TABLE, usize);
 
-pub(crate) fn try_hook_panel_map() -> bool {
-    // let task_name = "fld_panel_map";
-    // let task_name = "road map(FLD)";
-    let task_name = "fld_panel";
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut(task_name) {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked fldPanelMapUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, fldPanelMapUpdate);
-        true
-    } else {
-        false
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn fldPanelMapUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
-}
-
-pub(crate) fn try_hook_date_draw() -> bool {
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut("date draw") {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked dateDrawUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, dateDrawUpdate);
-        true
-    } else {
-        false
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn dateDrawUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
-}
-
-pub(crate) fn try_hook_mission_draw() -> bool {
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut("draw mission list(FLD)") {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked missionDrawUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, missionDrawUpdate);
-        true
-    } else {
-        false
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn missionDrawUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
-}
-
-pub(crate) fn try_hook_party_panel() -> bool {
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut("btlPartyPanel") {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked btlPartyPanelUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, btlPartyPanelUpdate);
-        true
-    } else {
-        false
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn btlPartyPanelUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
-}
-
-pub(crate) fn try_hook_roadmap() -> bool {
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut("road map(FLD)") {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked fldRoadmapUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, fldRoadmapUpdate);
-        true
-    } else {
-        false
-    }
-}
-
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn fldRoadmapUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
-}
-
-// fldPanelTipUpdate
-
-pub(crate) fn try_hook_casino_coin() -> bool {
-    if let Some(task) = GfdTask::<GfdAllocator, ()>::find_by_str_mut("fldPanelTipUpdate") {
-        let update_fn = task.get_update_ptr() as usize;
-        logln!(Verbose, "Hooked fldPanelTipUpdate: 0x{:x}", update_fn);
-        create_hook!(update_fn, fldPanelTipUpdate);
-        true
-    } else {
-        false
-    }
+// HUD tasks registered in state/hud.rs get one of these hooks, indexed by slot.
+// the slot count comes from this list, so hooks and the registry can't drift apart
+macro_rules! hud_slot_hooks {
+    ($($slot:literal => $name:ident),* $(,)?) => {
+        pub(crate) const HUD_HOOK_SLOT_COUNT: usize = [$($slot),*].len();
+        // slots index a bit mask, so they have to run 0..count in order
+        const _: () = {
+            let slots: [usize; HUD_HOOK_SLOT_COUNT] = [$($slot),*];
+            let mut i = 0;
+            while i < slots.len() { assert!(slots[i] == i); i += 1; }
+            assert!(HUD_HOOK_SLOT_COUNT <= u32::BITS as usize);
+        };
+        pub(crate) fn hook_hud_slot(slot: usize, update_fn: usize) {
+            match slot {
+                $($slot => create_hook!(update_fn, $name),)*
+                _ => logln!(Verbose, "Invalid HUD hook slot {}", slot)
+            }
+        }
+        $(
+            #[riri_hook_fn(user_defined())]
+            pub unsafe extern "C" fn $name(p_this: *mut u8, delta: f32) -> u64 {
+                if !is_hud_slot_hidden($slot) {
+                    original_function!(p_this, delta)
+                } else {
+                    TaskFunctionReturn::Continue as u64
+                }
+            }
+        )*
+    };
 }
 
-#[riri_hook_fn(user_defined())]
-pub unsafe extern "C" fn fldPanelTipUpdate(p_this: *mut u8, delta: f32) -> u64 {
-    if !Freecam::check_active() {
-        original_function!(p_this, delta)
-    } else {
-        TaskFunctionReturn::Continue as u64
-    }
+hud_slot_hooks! {
+    0 => hudTaskUpdate0, 1 => hudTaskUpdate1, 2 => hudTaskUpdate2, 3 => hudTaskUpdate3,
+    4 => hudTaskUpdate4, 5 => hudTaskUpdate5, 6 => hudTaskUpdate6, 7 => hudTaskUpdate7,
+    8 => hudTaskUpdate8, 9 => hudTaskUpdate9, 10 => hudTaskUpdate10, 11 => hudTaskUpdate11,
 }
 
 #[no_mangle]
@@ -4,7 +4,7 @@ use opengfd::kernel::allocator::GfdAllocator;
 use riri_mod_tools_proc::riri_hook_fn;
 use riri_mod_tools_rt::{logln, sigscan_resolver};
 use crate::state::camera::Freecam;
-use opengfd::kernel::task::Task as GfdTask;
+use opengfd::kernel::task::{Task as GfdTask, UpdateTask};
 
 #[no_mangle]
 pub unsafe extern "C" fn setTITLE_RES_PROC_LOOP(ofs: usize) -> Option<NonNull<u8>> {
@@ -26,6 +26,9 @@ pub unsafe extern "C" fn TITLE_RES_PROC_LOOP(p_task: *mut u8) -> u64 {
     if INITIALIZED_FREECAM.get().is_none() {
         let new_task = GfdTask::<GfdAllocator, Freecam>::new_update(10, 0, 0, 0, GfdAllocator);
         logln!(Information, "Freecam task: {}", new_task);
+        if let Some(task) = GfdTask::<GfdAllocator, Freecam>::find_by_str_mut(Freecam::NAME) {
+            task.get_main_work_mut().unwrap().restore_session();
+        }
         let _ = INITIALIZED_FREECAM.set(());
     }
     original_function!(p_task)
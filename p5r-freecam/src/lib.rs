@@ -15,15 +15,36 @@ pub mod gui {
 pub mod hooks {
     pub mod battle;
     pub mod event;
+    pub mod event_command;
     pub mod field;
     pub mod title;
 }
 pub mod state {
+    pub mod auto_speed;
+    pub mod battle_view;
+    pub mod bookmark;
     pub mod camera;
+    pub mod capture;
+    pub mod clip;
     pub mod controls;
+    pub mod dolly;
+    pub mod event_command;
+    pub mod event_trace;
+    pub mod follow;
+    pub mod horizon;
+    pub mod hud;
+    pub mod interchange;
     pub mod io;
+    pub mod mouse_keyboard;
     pub mod node;
+    pub mod orbit;
+    pub mod orientation;
+    pub mod pad;
     pub mod path;
+    pub mod session;
+    pub mod smoothing;
+    pub mod speed;
+    pub mod timing;
     pub mod window;
 }
 pub mod version;
\ No newline at end of file
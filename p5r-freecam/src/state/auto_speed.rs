@@ -0,0 +1,139 @@
+use imgui::{TreeNodeFlags, Ui};
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::kernel::task::Task as GfdTask;
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use xrd744_lib::btl::package::Package;
+use crate::state::camera::Freecam;
+
+pub(crate) const AUTO_SPEED_DEFAULT_SLOW: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+pub enum EnterSpeedBehaviour {
+    Keep,
+    Freeze,
+    Slow
+}
+
+impl EnterSpeedBehaviour {
+    pub const ALL: [Self; 3] = [ Self::Keep, Self::Freeze, Self::Slow ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Keep => "Keep Speed",
+            Self::Freeze => "Freeze",
+            Self::Slow => "Slow Motion"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedContext {
+    Field,
+    Battle
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct AutoSpeedSettings {
+    pub(crate) on_enter: EnterSpeedBehaviour,
+    pub(crate) slow_speed: f32,
+    pub(crate) restore_on_exit: bool,
+    // use the last freecam speed for field and battle instead of on_enter
+    pub(crate) remember_per_context: bool,
+    pub(crate) field_speed: Option<f32>,
+    pub(crate) battle_speed: Option<f32>,
+}
+
+impl Default for AutoSpeedSettings {
+    fn default() -> Self {
+        Self {
+            on_enter: EnterSpeedBehaviour::Keep,
+            slow_speed: AUTO_SPEED_DEFAULT_SLOW,
+            restore_on_exit: true,
+            remember_per_context: false,
+            field_speed: None,
+            battle_speed: None
+        }
+    }
+}
+
+impl Freecam {
+    pub(crate) fn get_speed_context() -> SpeedContext {
+        match GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle") {
+            Some(_) => SpeedContext::Battle,
+            None => SpeedContext::Field
+        }
+    }
+
+    fn get_context_speed_mut(&mut self, context: SpeedContext) -> &mut Option<f32> {
+        match context {
+            SpeedContext::Field => &mut self.auto_speed.field_speed,
+            SpeedContext::Battle => &mut self.auto_speed.battle_speed
+        }
+    }
+
+    pub(crate) fn apply_enter_speed(&mut self) {
+        let current = match Self::get_frequency_speed() {
+            Some(v) => v,
+            None => return
+        };
+        self.speed_before_freecam = Some(current);
+        let context = Self::get_speed_context();
+        let remembered = match self.auto_speed.remember_per_context {
+            true => *self.get_context_speed_mut(context),
+            false => None
+        };
+        match (remembered, self.auto_speed.on_enter) {
+            (Some(v), _) if v == 0. => self.pause_game(),
+            (None, EnterSpeedBehaviour::Freeze) => self.pause_game(),
+            (Some(v), _) => Self::set_frequency_speed(v),
+            (None, EnterSpeedBehaviour::Slow) => Self::set_frequency_speed(self.auto_speed.slow_speed),
+            (None, EnterSpeedBehaviour::Keep) => ()
+        }
+    }
+
+    pub(crate) fn apply_exit_speed(&mut self) {
+        // pause and stepping are only handled while freecam is active, so settle them now
+        let speed = self.settle_time_control();
+        if self.auto_speed.remember_per_context {
+            let context = Self::get_speed_context();
+            *self.get_context_speed_mut(context) = speed;
+        }
+        if let Some(speed) = self.speed_before_freecam.take() {
+            if self.auto_speed.restore_on_exit {
+                Self::set_frequency_speed(speed);
+                logln!(Verbose, "Restored game speed to {:.02}x", speed);
+            }
+        }
+    }
+
+    pub(crate) fn draw_contents_auto_speed(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Game Speed##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        let mut behaviour = EnterSpeedBehaviour::ALL.iter().position(|b| *b == self.auto_speed.on_enter).unwrap();
+        ui.set_next_item_width(max_width / 4.);
+        if ui.combo("On Enable##ForFreecamAutoSpeed", &mut behaviour, &EnterSpeedBehaviour::ALL, |b| b.get_name().into()) {
+            self.auto_speed.on_enter = EnterSpeedBehaviour::ALL[behaviour];
+        }
+        ui.disabled(self.auto_speed.on_enter != EnterSpeedBehaviour::Slow, || {
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(max_width / 6.);
+            if ui.input_float("Slow Speed##ForFreecamAutoSpeed", &mut self.auto_speed.slow_speed).step(0.05).display_format("%.2f").build() {
+                self.auto_speed.slow_speed = self.auto_speed.slow_speed.max(0.);
+            }
+        });
+        ui.checkbox("Restore Speed On Disable##ForFreecamAutoSpeed", &mut self.auto_speed.restore_on_exit);
+        ui.same_line_with_spacing(0., 10.);
+        ui.checkbox("Remember Field/Battle Speed##ForFreecamAutoSpeed", &mut self.auto_speed.remember_per_context);
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Enabling freecam goes back to the speed last used in the same context");
+        }
+        let fmt = |v: Option<f32>| v.map_or("-".to_owned(), |v| format!("{:.02}x", v));
+        ui.text(format!("Field: {}  Battle: {}", fmt(self.auto_speed.field_speed), fmt(self.auto_speed.battle_speed)));
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Forget##ForFreecamAutoSpeed") {
+            self.auto_speed.field_speed = None;
+            self.auto_speed.battle_speed = None;
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use imgui::{TreeNodeFlags, Ui};
+use rkyv::{Archive, Deserialize, Serialize};
+use xrd744_lib::btl::camera::CameraType;
+use crate::state::camera::Freecam;
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct BattleViewSettings {
+    // one bit per CameraType, set bits are driven by freecam
+    pub(crate) override_views: u64,
+}
+
+impl Default for BattleViewSettings {
+    fn default() -> Self {
+        Self { override_views: u64::MAX }
+    }
+}
+
+impl BattleViewSettings {
+    pub(crate) fn is_overridden(&self, view: CameraType) -> bool {
+        self.override_views & (1 << view as u32) != 0
+    }
+
+    pub(crate) fn set_overridden(&mut self, view: CameraType, value: bool) {
+        match value {
+            true => self.override_views |= 1 << view as u32,
+            false => self.override_views &= !(1 << view as u32)
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BattleViewState {
+    pub(crate) settings: BattleViewSettings,
+    // last view seen by the Usually camera update
+    pub(crate) current: Option<CameraType>,
+}
+
+fn get_camera_types() -> impl Iterator<Item = CameraType> {
+    (0..=CameraType::Prepareview as u32).filter_map(|v| CameraType::try_from(v).ok())
+}
+
+impl Freecam {
+    pub(crate) fn draw_contents_battle_view(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Battle Camera##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        ui.text("Use freecam during these views");
+        if let Some(view) = self.battle_view.current {
+            ui.same_line_with_spacing(0., 10.);
+            ui.text_disabled(format!("(last: {:?})", view));
+        }
+        if ui.button("All##ForFreecamBattleView") { self.battle_view.settings.override_views = u64::MAX; }
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("None##ForFreecamBattleView") { self.battle_view.settings.override_views = 0; }
+        let settings = &mut self.battle_view.settings;
+        ui.child_window("##ListForFreecamBattleView").size([0., 200.]).build(|| {
+            for view in get_camera_types() {
+                let mut enabled = settings.is_overridden(view);
+                if ui.checkbox(format!("{:?}##ForFreecamBattleView", view), &mut enabled) {
+                    settings.set_overridden(view, enabled);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,212 @@
+use glam::{EulerRot, Quat, Vec3A};
+use imgui::{TreeNodeFlags, Ui};
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::kernel::task::Task as GfdTask;
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use windows::Win32::UI::Input::KeyboardAndMouse::{VIRTUAL_KEY, VK_1, VK_CONTROL, VK_MENU};
+use xrd744_lib::btl::package::Package;
+use xrd744_lib::fld::main::Main as FldMain;
+use crate::state::camera::Freecam;
+use crate::state::node::FreecamNode;
+
+pub(crate) const BOOKMARK_SLOT_COUNT: usize = 9;
+pub(crate) const BOOKMARK_DEFAULT_BLEND_TIME: f32 = 1.;
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct CameraBookmark {
+    pub(crate) node: FreecamNode,
+    pub(crate) fovy: Option<f32>,
+}
+
+// field maps are identified by their major/minor id
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct CameraBookmarkSet {
+    pub(crate) map: [u16; 2],
+    pub(crate) slots: [Option<CameraBookmark>; BOOKMARK_SLOT_COUNT],
+}
+
+impl CameraBookmarkSet {
+    fn new(map: [u16; 2]) -> Self {
+        Self { map, slots: Default::default() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct BookmarkSettings {
+    // last map reported by the game
+    pub(crate) map: [u16; 2],
+    pub(crate) blend: bool,
+    pub(crate) blend_time: f32,
+    pub(crate) sets: Vec<CameraBookmarkSet>,
+}
+
+impl Default for BookmarkSettings {
+    fn default() -> Self {
+        Self { map: [0; 2], blend: true, blend_time: BOOKMARK_DEFAULT_BLEND_TIME, sets: vec![] }
+    }
+}
+
+#[derive(Debug)]
+pub struct BookmarkBlend {
+    from: CameraBookmark,
+    to: CameraBookmark,
+    time: f32,
+}
+
+// battles keep the id of the field they were entered from
+fn get_current_map() -> Option<[u16; 2]> {
+    let (major, minor) = match GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle")
+        .and_then(|task| task.get_main_work()) {
+        Some(pkg) => pkg.get_field_id(),
+        None => FldMain::get_current()?.get_field_id()
+    };
+    Some([major, minor])
+}
+
+impl Freecam {
+    pub(crate) fn update_bookmark_map(&mut self) {
+        let map = match get_current_map() {
+            Some(v) => v, None => return
+        };
+        if map != self.bookmarks.map {
+            self.bookmarks.map = map;
+            self.mark_session_dirty();
+            logln!(Verbose, "Switched to bookmarks for map {:03}_{:03}", map[0], map[1]);
+        }
+    }
+
+    fn get_bookmark_set(&self) -> Option<&CameraBookmarkSet> {
+        self.bookmarks.sets.iter().find(|s| s.map == self.bookmarks.map)
+    }
+
+    fn get_bookmark_set_mut(&mut self) -> &mut CameraBookmarkSet {
+        let map = self.bookmarks.map;
+        match self.bookmarks.sets.iter().position(|s| s.map == map) {
+            Some(i) => &mut self.bookmarks.sets[i],
+            None => {
+                self.bookmarks.sets.push(CameraBookmarkSet::new(map));
+                self.bookmarks.sets.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn get_current_bookmark(&self) -> CameraBookmark {
+        CameraBookmark {
+            node: FreecamNode::new_euler(self.camera_pos, self.pan, self.pitch, self.roll),
+            fovy: Self::get_scene_camera().map(|c| c.get_fovy())
+        }
+    }
+
+    pub(crate) fn store_bookmark(&mut self, slot: usize) {
+        let bookmark = self.get_current_bookmark();
+        self.get_bookmark_set_mut().slots[slot] = Some(bookmark);
+        self.mark_session_dirty();
+        logln!(Verbose, "Stored bookmark {} for map {:03}_{:03}", slot + 1, self.bookmarks.map[0], self.bookmarks.map[1]);
+    }
+
+    pub(crate) fn recall_bookmark(&mut self, slot: usize) {
+        let bookmark = match self.get_bookmark_set().and_then(|s| s.slots[slot].clone()) {
+            Some(v) => v,
+            None => {
+                logln!(Verbose, "Bookmark {} is empty", slot + 1);
+                return;
+            }
+        };
+        match self.bookmarks.blend && self.bookmarks.blend_time > 0. {
+            true => self.bookmark_blend = Some(BookmarkBlend { from: self.get_current_bookmark(), to: bookmark, time: 0. }),
+            false => self.set_bookmark_pose(&bookmark)
+        }
+        logln!(Verbose, "Recalled bookmark {}", slot + 1);
+    }
+
+    fn set_bookmark_pose(&mut self, bookmark: &CameraBookmark) {
+        let (pan, pitch, roll) = bookmark.node.rot.to_euler(EulerRot::YXZEx);
+        self.teleport_camera(bookmark.node.trans, pan, pitch, roll);
+        if let (Some(fovy), Some(cam)) = (bookmark.fovy, Self::get_scene_camera_mut()) {
+            cam.set_fovy(fovy);
+        }
+    }
+
+    // returns true while a recall is in progress, manual input is ignored until it finishes
+    pub(crate) fn update_bookmark_blend(&mut self, delta: f32) -> bool {
+        let blend = match self.bookmark_blend.as_mut() {
+            Some(v) => v,
+            None => return false
+        };
+        blend.time = (blend.time + delta).min(self.bookmarks.blend_time);
+        let t = match self.bookmarks.blend_time > 0. {
+            true => blend.time / self.bookmarks.blend_time,
+            false => 1.
+        };
+        let t = t * t * (3. - 2. * t);
+        let done = t >= 1.;
+        let pose = CameraBookmark {
+            node: FreecamNode::new(
+                Vec3A::lerp(blend.from.node.trans, blend.to.node.trans, t),
+                Quat::slerp(blend.from.node.rot, blend.to.node.rot, t)
+            ),
+            fovy: match (blend.from.fovy, blend.to.fovy) {
+                (Some(a), Some(b)) => Some(a + (b - a) * t),
+                (_, b) => b
+            }
+        };
+        self.set_bookmark_pose(&pose);
+        if done { self.bookmark_blend = None; }
+        true
+    }
+
+    // Ctrl + 1-9 stores, Alt + 1-9 recalls. This runs before the path shortcuts so they don't see the digit
+    pub fn update_bookmarks(&mut self) {
+        self.update_bookmark_map();
+        let store = Self::check_key_held(VK_CONTROL);
+        let recall = Self::check_key_held(VK_MENU);
+        if !store && !recall { return; }
+        for slot in 0..BOOKMARK_SLOT_COUNT {
+            if Self::check_key_pressed(VIRTUAL_KEY(VK_1.0 + slot as u16)) {
+                match store {
+                    true => self.store_bookmark(slot),
+                    false => self.recall_bookmark(slot)
+                }
+            }
+        }
+    }
+
+    pub(crate) fn draw_contents_bookmarks(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Bookmarks##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        self.update_bookmark_map();
+        ui.text(format!("Map {:03}_{:03}", self.bookmarks.map[0], self.bookmarks.map[1]));
+        if get_current_map().is_none() {
+            ui.same_line_with_spacing(0., 10.);
+            ui.text_disabled("(not in a field)");
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.checkbox("Blend##ForFreecamBookmarks", &mut self.bookmarks.blend);
+        ui.disabled(!self.bookmarks.blend, || {
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(max_width / 5.);
+            if ui.input_float("##BlendTimeForFreecamBookmarks", &mut self.bookmarks.blend_time).display_format("%.2f sec").build() {
+                self.bookmarks.blend_time = self.bookmarks.blend_time.max(0.);
+            }
+        });
+        let filled: Vec<bool> = (0..BOOKMARK_SLOT_COUNT)
+            .map(|i| self.get_bookmark_set().map_or(false, |s| s.slots[i].is_some()))
+            .collect();
+        let button_width = (max_width - 10. * (BOOKMARK_SLOT_COUNT - 1) as f32) / BOOKMARK_SLOT_COUNT as f32;
+        for (i, filled) in filled.iter().enumerate() {
+            if i > 0 { ui.same_line_with_spacing(0., 10.); }
+            ui.disabled(!filled, || {
+                if ui.button_with_size(format!("{}##RecallForFreecamBookmarks", i + 1), [button_width, 0.]) {
+                    self.recall_bookmark(i);
+                }
+            });
+        }
+        for i in 0..BOOKMARK_SLOT_COUNT {
+            if i > 0 { ui.same_line_with_spacing(0., 10.); }
+            if ui.button_with_size(format!("Set##{}StoreForFreecamBookmarks", i), [button_width, 0.]) {
+                self.store_bookmark(i);
+            }
+        }
+    }
+}
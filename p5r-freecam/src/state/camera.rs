@@ -1,19 +1,39 @@
+use std::collections::BTreeSet;
 use std::error::Error;
 use std::num::NonZeroUsize;
+use std::time::Instant;
 use bitflags::bitflags;
-use glam::{EulerRot, Mat4, Quat, Vec3A, Vec4};
-use opengfd::io::controller::ControllerButton;
+use glam::{Mat4, Vec3, Vec3A, Vec4};
 use opengfd::kernel::allocator::GfdAllocator;
 use opengfd::kernel::graphics::GraphicsGlobal;
 use opengfd::object::camera::Camera as GfdCamera;
 use riri_mod_tools_rt::logln;
 use xrd744_lib::btl::package::Package;
 use crate::gui::utils::Shortcut;
+use crate::state::auto_speed::AutoSpeedSettings;
+use crate::state::battle_view::BattleViewState;
+use crate::state::bookmark::{BookmarkBlend, BookmarkSettings};
+use crate::state::capture::CaptureState;
+use crate::state::clip::{ClipRestore, ClipSettings};
+use crate::state::dolly::DollyState;
+use crate::state::event_command::EvtCommandState;
+use crate::state::event_trace::EvtTraceState;
+use crate::state::follow::FollowState;
+use crate::state::horizon::{HorizonSettings, LevelBlend};
+use crate::state::hud::HudState;
+use crate::state::interchange::InterchangeSettings;
+use crate::state::mouse_keyboard::MouseKeyboardState;
 use crate::state::node::FreecamNode;
+use crate::state::orbit::{CameraMode, OrbitState};
+use crate::state::orientation::OrientationState;
+use crate::state::pad::PadSettings;
+use crate::state::session::FreecamSession;
+use crate::state::smoothing::FreecamSmoothing;
+use crate::state::speed::SpeedState;
+use crate::state::timing::TimeControl;
 use opengfd::kernel::task::{InitTask, Task as GfdTask, TaskFunctionReturn, UpdateTask};
 use windows::Win32::UI::Input::KeyboardAndMouse::{VK_0, VK_ADD, VK_F4, VK_NUMPAD0, VK_OEM_MINUS, VK_OEM_PLUS, VK_SUBTRACT};
 use xrd744_lib::fld::camera::Camera as FldCamera;
-use glam::Vec4Swizzles;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum CameraState {
@@ -35,12 +55,7 @@ bitflags! {
         const DEBUG_HOOKED_FREE_CAM_LOOP = 1 << 7;
         const PLAYING_PATH = 1 << 8;
         const PLAYER_INPUT_LOCKED = 1 << 9;
-        const HOOKED_PANEL_MAP = 1 << 0x10;
-        const HOOKED_DATE_DRAW = 1 << 0x11;
-        const HOOKED_MISSION_DRAW = 1 << 0x12;
-        const HOOKED_BATTLE_PARTY_PANEL = 1 << 0x13;
-        const HOOKED_ROADMAP = 1 << 0x14;
-        const HOOKED_CASINO_COIN = 1 << 0x15;
+        const HOOKED_GAME_WINDOW = 1 << 10;
         const CLOSED_DEBUG_WINDOW = 1 << 0x1f;
     }
 }
@@ -55,14 +70,50 @@ pub struct Freecam {
     pub(crate) camera_pos: Vec3A,
     pub(crate) lookat_pos: Vec3A,
     pub(crate) up_vec: Vec3A,
+    pub(crate) mouse_keyboard: MouseKeyboardState,
+    pub(crate) pad: PadSettings,
+    pub(crate) speed: SpeedState,
+    pub(crate) last_view_update: Option<Instant>,
+    pub(crate) smoothing: FreecamSmoothing,
+    pub(crate) orientation: OrientationState,
+    pub(crate) horizon: HorizonSettings,
+    pub(crate) level_blend: Option<LevelBlend>,
+    pub(crate) camera_mode: CameraMode,
+    pub(crate) orbit: OrbitState,
+    pub(crate) follow: FollowState,
+    pub(crate) dolly: DollyState,
+    pub(crate) clip: ClipSettings,
+    pub(crate) clip_restore: ClipRestore,
+    pub(crate) time: TimeControl,
+    pub(crate) hud: HudState,
+    pub(crate) evt_commands: EvtCommandState,
+    pub(crate) evt_trace: EvtTraceState,
+    pub(crate) capture: CaptureState,
+    pub(crate) battle_view: BattleViewState,
+    pub(crate) auto_speed: AutoSpeedSettings,
+    pub(crate) speed_before_freecam: Option<f32>,
+    pub(crate) bookmarks: BookmarkSettings,
+    pub(crate) bookmark_blend: Option<BookmarkBlend>,
     // camera path
     pub(crate) nodes: Vec<FreecamNode>,
     pub(crate) node_path_time: f32,
     pub(crate) node_path_current: f32,
     pub(crate) node_path_percent: f32,
+    pub(crate) node_selection: BTreeSet<usize>,
+    // node that shift-click ranges extend from
+    pub(crate) node_selection_anchor: Option<usize>,
     // send to evt task
     pub(crate) last_interp: FreecamNode,
     pub(crate) return_node: FreecamNode,
+    // import/export
+    pub(crate) interchange: InterchangeSettings,
+    // session persistence
+    pub(crate) saved_session: Option<FreecamSession>,
+    pub(crate) session_save_timer: f32,
+    pub(crate) session_dirty: bool,
+    pub(crate) pending_fovy: Option<f32>,
+    pub(crate) pending_game_speed: Option<f32>,
+    pub(crate) window_rect: Option<[i32; 4]>,
 
     // GUI
     pub(crate) shortcuts: Vec<Shortcut<Self>>,
@@ -74,17 +125,35 @@ pub(crate) const NODE_PATH_STEP: f32 = 0.25;
 
 pub(crate) const BSPLINE_DEGREE_QUADRATIC: usize = 2;
 
+// freecam speeds at full stick deflection, in units (or radians) per second
+pub(crate) const PAD_STICK_RANGE: f32 = 128.;
+pub(crate) const FREECAM_LOOK_SPEED: f32 = 0.96;
+pub(crate) const FREECAM_MOVE_SPEED: f32 = 768.;
+pub(crate) const FREECAM_VERTICAL_SPEED: f32 = 300.;
+pub(crate) const FREECAM_ROLL_SPEED: f32 = 6.;
+// avoid a large jump on the first update after a hitch or after freecam was turned off
+pub(crate) const FREECAM_MAX_DELTA: f32 = 0.1;
+
 impl Freecam {
 
     pub fn enable_freecam_mode(&mut self) {
         self.flags |= FreecamFlags::ACTIVE | FreecamFlags::SET_INITIAL_STATE;
+        self.last_view_update = None;
+        self.bookmark_blend = None;
+        self.reset_smoothing();
+        self.reset_clip_restore();
+        self.reset_mouse_keyboard();
         FldCamera::handle_freecam_onoff(true);
+        self.apply_enter_speed();
         logln!(Verbose, "Enable freecam");
     }
 
     pub fn disable_freecam_mode(&mut self) {
         self.flags &= !FreecamFlags::ACTIVE;
+        self.restore_scene_clip_planes();
         FldCamera::handle_freecam_onoff(false);
+        self.apply_exit_speed();
+        self.mark_session_dirty();
         logln!(Verbose, "Disable freecam");
     }
 
@@ -112,6 +181,7 @@ impl Freecam {
     pub fn lock_camera_position(&mut self) {
         if Self::check_key_pressed(VK_0) || Self::check_key_pressed(VK_NUMPAD0) {
             self.flags ^= FreecamFlags::LOCK_CAMERA_MOVEMENT;
+            self.mark_session_dirty();
             match self.flags.contains(FreecamFlags::LOCK_CAMERA_MOVEMENT) {
                 true => logln!(Verbose, "Camera is locked"),
                 false => logln!(Verbose, "Camera is unlocked"),
@@ -138,21 +208,38 @@ impl Freecam {
         })
     }
 
-    pub fn change_frequency_speed(&self, slow: bool) {
-        let glb = GraphicsGlobal::get_gfd_graphics_global_mut();
+    pub fn change_frequency_speed(&mut self, slow: bool) {
+        // keep stepping from where a running ramp is headed
+        if let Some(freq) = self.get_ramp_target().or(Self::get_frequency_speed()) {
+            let new_freq = (freq + if slow { -FREQUENCY_SPEED_TICK } else { FREQUENCY_SPEED_TICK }).max(0.);
+            logln!(Verbose, "New game speed: {:.02}x", new_freq);
+            self.ramp_frequency_speed(new_freq);
+            self.mark_session_dirty();
+        }
+    }
+
+    // battle frequency takes priority over the scene frequency while a battle is running
+    pub fn get_frequency_speed() -> Option<f32> {
+        if let Some(btl) = GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle") {
+            let pkg = btl.get_main_work_mut().unwrap();
+            pkg.get_frequency().map(|f| f.get_time())
+        } else {
+            let glb = GraphicsGlobal::get_gfd_graphics_global();
+            glb.get_current_scene().map(|s| s.get_frequency())
+        }
+    }
+
+    pub fn set_frequency_speed(speed: f32) {
         if let Some(btl) = GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle") {
             let pkg = btl.get_main_work_mut().unwrap();
             if let Some(freq) = pkg.get_frequency_mut() {
-                let new_freq = (freq.get_time() + if slow { -FREQUENCY_SPEED_TICK } else { FREQUENCY_SPEED_TICK }).max(0.);
-                logln!(Verbose, "New game speed: {:.02}x", new_freq);
-                freq.set_time(new_freq);
+                // a running fade would pull the speed straight back to its target
+                freq.stop_fade();
+                freq.set_time(speed.max(0.));
             }
         } else {
-            if let Some(scn) = glb.get_current_scene_mut() {
-                let new_freq = (scn.get_frequency() + if slow { -FREQUENCY_SPEED_TICK } else { FREQUENCY_SPEED_TICK }).max(0.);
-                logln!(Verbose, "New game speed: {:.02}x", new_freq);
-                scn.set_frequency(new_freq);
-            }
+            let glb = GraphicsGlobal::get_gfd_graphics_global_mut();
+            if let Some(scn) = glb.get_current_scene_mut() { scn.set_frequency(speed.max(0.)); }
         }
     }
 
@@ -160,91 +247,98 @@ impl Freecam {
         let new = (self.node_path_time + if slow { -NODE_PATH_STEP } else { NODE_PATH_STEP }).max(NODE_PATH_STEP);
         logln!(Verbose, "New node path time: {:.02} sec", new);
         self.node_path_time = new;
+        self.mark_session_dirty();
     }
 
-    pub fn update_view_matrix(&mut self) -> Mat4 {
-        // handle camera inputs
-        let ctrl  = unsafe { crate::globals::get_pad_instance().unwrap() };
-        let lstick = ctrl.get_current().get_lstick();
-        let rstick = ctrl.get_current().get_rstick();
-        let buttons = ctrl.get_current().get_hold_press();
-
-        let (pan_speed, move_speed) = if buttons.contains(ControllerButton::LEFT_TRIGGER) { (2., 5.) }
-        else if buttons.contains(ControllerButton::RIGHT_TRIGGER) { (0.5, 0.2) }
-        else { (1., 1.) };
-
+    // unit vector pointing from the look-at position back towards the camera
+    pub(crate) fn get_back_vector(pan: f32, pitch: f32) -> Vec3A {
+        Vec3A::new(-(pan.sin() * pitch.cos()), pitch.sin(), -(pan.cos() * pitch.cos()))
+    }
 
-        self.pan -= rstick.get_horizontal() as f32 / (8000. / pan_speed);
-        self.pitch += rstick.get_vertical() as f32 / (8000. / pan_speed);
-        if !self.flags.contains(FreecamFlags::LOCK_CAMERA_MOVEMENT) {
-            let lh = lstick.get_horizontal() as f32 / (10. / move_speed);
-            let lv = lstick.get_vertical() as f32 / (10. / move_speed);
-            let dir: Vec3A = self.camera_pos - self.lookat_pos; // front vector
-            let r = Vec3A::Y.cross(dir).normalize_or_zero(); // right unit vector
-
-            self.camera_pos += lh * r + lv * dir.normalize_or_zero();
-            if buttons.contains(ControllerButton::LEFT_SHOULDER) { self.camera_pos.y += 5. * move_speed; }
-            if buttons.contains(ControllerButton::RIGHT_SHOULDER) { self.camera_pos.y -= 5. * move_speed; }
-        }
-        if buttons.contains(ControllerButton::DPAD_UP) { self.roll += 0.1; }
-        if buttons.contains(ControllerButton::DPAD_DOWN) { self.roll -= 0.1; }
+    // convert (right, up, back) movement into world space for the given orientation
+    pub(crate) fn get_move_vector(pan: f32, pitch: f32, local: Vec3) -> Vec3A {
+        let dir = Self::get_back_vector(pan, pitch);
+        let r = Vec3A::Y.cross(dir).normalize_or_zero(); // right unit vector
+        local.x * r + local.y * Vec3A::Y + local.z * dir
+    }
 
+    pub(crate) fn update_lookat(&mut self) {
         // set lookat and up vec
-        self.lookat_pos = self.camera_pos - Vec3A::new(
-            -(self.pan.sin() * self.pitch.cos()),
-            self.pitch.sin(),
-            -(self.pan.cos() * self.pitch.cos()),
-        ) * 100.;
+        self.lookat_pos = self.camera_pos - Self::get_back_vector(self.pan, self.pitch) * 100.;
 
         let dir: Vec3A = self.camera_pos - self.lookat_pos;
         let r = Vec3A::Y.cross(dir).normalize_or_zero();
         self.up_vec = dir.cross(r).normalize_or_zero().into();
+    }
 
-        Mat4::look_at_rh(self.camera_pos.into(), self.lookat_pos.into(), self.up_vec.into())
+    // real time since the view was last updated. the field, event and battle hooks can all update
+    // the view in the same frame, so this keeps motion independent of both frame rate and hook
+    fn get_view_delta(&mut self) -> f32 {
+        let now = Instant::now();
+        let delta = self.last_view_update.map_or(0., |t| now.duration_since(t).as_secs_f32());
+        self.last_view_update = Some(now);
+        delta.min(FREECAM_MAX_DELTA)
     }
-}
 
-impl Freecam {
-    fn update_ui_element_visibility(&mut self) {
-        // hooked UI elements
-        if !self.flags.contains(FreecamFlags::HOOKED_PANEL_MAP) {
-            if crate::hooks::field::try_hook_panel_map() {
-                self.flags |= FreecamFlags::HOOKED_PANEL_MAP;
-            }
-        }
-        if !self.flags.contains(FreecamFlags::HOOKED_DATE_DRAW) {
-            if crate::hooks::field::try_hook_date_draw() {
-                self.flags |= FreecamFlags::HOOKED_DATE_DRAW;
-            }
-        }
-        if !self.flags.contains(FreecamFlags::HOOKED_MISSION_DRAW) {
-            if crate::hooks::field::try_hook_mission_draw() {
-                self.flags |= FreecamFlags::HOOKED_MISSION_DRAW;
-            }
+    pub fn update_view_matrix(&mut self) -> Mat4 {
+        let delta = self.get_view_delta();
+        self.reset_orientation_view();
+        if self.update_bookmark_blend(delta) {
+            self.update_lookat();
+            return Mat4::look_at_rh(self.camera_pos.into(), self.lookat_pos.into(), self.up_vec.into());
         }
-        if !self.flags.contains(FreecamFlags::HOOKED_BATTLE_PARTY_PANEL) {
-            if crate::hooks::field::try_hook_party_panel() {
-                self.flags |= FreecamFlags::HOOKED_BATTLE_PARTY_PANEL;
-            }
+        // handle camera inputs
+        let pad = self.get_pad_input();
+
+        // keyboard and mouse sit alongside the pad
+        let kbm = self.get_mouse_keyboard_input();
+
+        let speed = &self.speed.settings;
+        let look = (pad.look * FREECAM_LOOK_SPEED * delta + kbm.look) * speed.look_speed;
+        // (pan, pitch, roll)
+        let rot_delta = Vec3::new(-look.x, look.y, pad.roll * FREECAM_ROLL_SPEED * speed.roll_speed * delta);
+        // (right, up, back)
+        let mut move_delta = Vec3::ZERO;
+        if !self.flags.contains(FreecamFlags::LOCK_CAMERA_MOVEMENT) {
+            let lh = pad.movement.x + kbm.movement.x;
+            let lv = pad.movement.z - kbm.movement.z;
+            let lift = pad.movement.y + kbm.movement.y;
+            move_delta = Vec3::new(lh * FREECAM_MOVE_SPEED, lift * FREECAM_VERTICAL_SPEED, lv * FREECAM_MOVE_SPEED)
+                * speed.move_speed * delta;
         }
-        if !self.flags.contains(FreecamFlags::HOOKED_ROADMAP) {
-            if crate::hooks::field::try_hook_roadmap() {
-                self.flags |= FreecamFlags::HOOKED_ROADMAP;
-            }
+        self.update_level_blend(delta);
+        // path playback drives the camera position directly
+        match self.camera_mode {
+            CameraMode::Orbit if !self.flags.contains(FreecamFlags::PLAYING_PATH) => self.apply_orbit_motion(rot_delta, move_delta),
+            CameraMode::Follow if !self.flags.contains(FreecamFlags::PLAYING_PATH) => self.apply_follow_motion(rot_delta, move_delta, delta),
+            CameraMode::Dolly if !self.flags.contains(FreecamFlags::PLAYING_PATH) => self.apply_dolly_motion(move_delta),
+            CameraMode::Fly if self.orientation.enabled && !self.flags.contains(FreecamFlags::PLAYING_PATH) => self.apply_quaternion_motion(rot_delta, move_delta, delta),
+            _ => self.apply_manual_motion(rot_delta, move_delta, delta),
+        };
+        if self.camera_mode == CameraMode::Dolly && self.flags.contains(FreecamFlags::PLAYING_PATH) {
+            self.update_dolly_fovy_from_position();
         }
-        if !self.flags.contains(FreecamFlags::HOOKED_CASINO_COIN) {
-            if crate::hooks::field::try_hook_casino_coin() {
-                self.flags |= FreecamFlags::HOOKED_CASINO_COIN;
-            }
+        if self.is_quaternion_view_active() {
+            return self.get_quaternion_view_matrix();
         }
+
+        self.update_lookat();
+        Mat4::look_at_rh(self.camera_pos.into(), self.lookat_pos.into(), self.up_vec.into())
     }
+}
 
+impl Freecam {
     pub(crate) fn get_scene_camera() -> Option<&'static GfdCamera> {
         let graphics = GraphicsGlobal::get_gfd_graphics_global();
         graphics.get_current_scene()
                 .and_then(|scn| scn.get_current_camera())
     }
 
+    pub(crate) fn get_field_camera() -> Option<&'static mut FldCamera> {
+        GfdTask::<GfdAllocator, FldCamera>::find_by_str_mut("field camera CTRL")
+            .and_then(|task| task.get_main_work_mut())
+    }
+
     pub(crate) fn get_scene_camera_mut() -> Option<&'static mut GfdCamera> {
         let graphics = GraphicsGlobal::get_gfd_graphics_global_mut();
         graphics.get_current_scene_mut()
@@ -267,34 +361,35 @@ impl UpdateTask for Freecam {
         if ctx.flags.contains(FreecamFlags::ACTIVE) {
             ctx.update_scene_speed();
             ctx.lock_camera_position();
+            ctx.update_camera_mode();
+            ctx.update_level_horizon();
+            ctx.update_speed_preset();
+            ctx.update_time_control();
         }
         if ctx.flags.contains(FreecamFlags::ACTIVE) {
+            ctx.update_bookmarks();
             ctx.update_camera_path(delta);
         }
 
         if ctx.flags.contains(FreecamFlags::SET_INITIAL_STATE) {
             if let Some(cam) = Self::get_scene_camera() {
-                let inv = cam.get_view_transform().inverse();
-                (ctx.pan, ctx.pitch, ctx.roll) = inv.to_euler(EulerRot::YXZEx);
-                ctx.pan = match ctx.pan >= 0. {
-                    true => -(std::f32::consts::PI - ctx.pan),
-                    false => ctx.pan + std::f32::consts::PI
-                };
-                ctx.camera_pos = inv.w_axis.xyz().into();
-                let ret_rot = Quat::from_euler(EulerRot::YXZEx, ctx.pan, ctx.pitch, ctx.roll);
-                ctx.return_node = FreecamNode::new(ctx.camera_pos, ret_rot);
+                (ctx.camera_pos, ctx.pan, ctx.pitch, ctx.roll) = FreecamNode::get_view_euler(cam.get_view_transform());
+                ctx.return_node = FreecamNode::new_euler(ctx.camera_pos, ctx.pan, ctx.pitch, ctx.roll);
             }
+            ctx.reset_smoothing();
+            ctx.apply_pending_session();
             ctx.flags &= !FreecamFlags::SET_INITIAL_STATE;
         }
         if ctx.flags.contains(FreecamFlags::ACTIVE) {
             if let Some(cam) = Self::get_scene_camera_mut() {
                 cam.set_view_transform(ctx.update_view_matrix());
-                cam.set_roll(ctx.roll);
+                cam.set_roll(ctx.get_view_roll());
+                ctx.apply_clip_planes(cam);
             }
         }
         // open window
         if !ctx.flags.contains(FreecamFlags::OPENED_DEBUG_WINDOW) {
-            if let Err(e) = crate::gui::app::init_debug_window() {
+            if let Err(e) = crate::gui::app::init_debug_window(ctx.window_rect) {
                 logln!(Error, "An error occurred while initializing the debugger GUI: {}", e);
             }
             ctx.flags |= FreecamFlags::OPENED_DEBUG_WINDOW;
@@ -307,11 +402,20 @@ impl UpdateTask for Freecam {
                 Err(e) => logln!(Error, "An error occurred while executing the debugger GUI: {}", e),
             }
         }
+        ctx.update_speed_ramp();
         ctx.update_ui_element_visibility();
+        ctx.update_evt_commands();
+        ctx.update_evt_trace();
+        ctx.update_session(delta);
         TaskFunctionReturn::Continue
     }
-    fn shutdown(_task: &mut GfdTask<GfdAllocator, Self>) -> ()
-    where Self: Sized {}
+    fn shutdown(task: &mut GfdTask<GfdAllocator, Self>) -> ()
+    where Self: Sized {
+        let ctx = task.get_main_work_mut().unwrap();
+        ctx.restore_scene_clip_planes();
+        ctx.unhook_game_window();
+        ctx.save_session();
+    }
 }
 
 impl InitTask for Freecam {
@@ -324,12 +428,45 @@ impl InitTask for Freecam {
             camera_pos: Vec3A::ZERO,
             lookat_pos: Vec3A::ZERO,
             up_vec: Vec3A::Y,
+            mouse_keyboard: MouseKeyboardState::default(),
+            pad: PadSettings::default(),
+            speed: SpeedState::default(),
+            last_view_update: None,
+            smoothing: FreecamSmoothing::default(),
+            orientation: OrientationState::default(),
+            horizon: HorizonSettings::default(),
+            level_blend: None,
+            camera_mode: CameraMode::Fly,
+            orbit: OrbitState::default(),
+            follow: FollowState::default(),
+            dolly: DollyState::default(),
+            clip: ClipSettings::default(),
+            clip_restore: ClipRestore::default(),
+            time: TimeControl::default(),
+            hud: HudState::default(),
+            evt_commands: EvtCommandState::default(),
+            evt_trace: EvtTraceState::default(),
+            capture: CaptureState::default(),
+            battle_view: BattleViewState::default(),
+            auto_speed: AutoSpeedSettings::default(),
+            speed_before_freecam: None,
+            bookmarks: BookmarkSettings::default(),
+            bookmark_blend: None,
             nodes: vec![],
             node_path_time: NODE_PATH_DEFAULT_TIME,
             node_path_current: 0.,
             node_path_percent: 0.,
+            node_selection: BTreeSet::new(),
+            node_selection_anchor: None,
             last_interp: FreecamNode::default(),
             return_node: FreecamNode::default(),
+            interchange: InterchangeSettings::default(),
+            saved_session: None,
+            session_save_timer: 0.,
+            session_dirty: false,
+            pending_fovy: None,
+            pending_game_speed: None,
+            window_rect: None,
             shortcuts: vec![],
         }
     }
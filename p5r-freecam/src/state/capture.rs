@@ -0,0 +1,172 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use imgui::{TreeNodeFlags, Ui};
+use opengfd::object::camera::Camera as GfdCamera;
+use riri_mod_tools_rt::logln;
+use crate::state::camera::{Freecam, FreecamFlags};
+use crate::state::event_command::get_evt_slot_commands;
+use crate::state::node::FreecamNode;
+
+static CAPTURE_ON_COMMAND: AtomicBool = AtomicBool::new(false);
+static EVT_CAMERA_COMMAND: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn is_camera_command(name: &str) -> bool {
+    name == "CSD_" || name == "CMD_"
+}
+
+pub(crate) fn note_evt_command(slot: usize) {
+    if !CAPTURE_ON_COMMAND.load(Ordering::Relaxed) { return; }
+    if get_evt_slot_commands(slot).any(|c| is_camera_command(&c.name)) {
+        EVT_CAMERA_COMMAND.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureMode {
+    EveryFrame,
+    // the game interpolates between these, so keys land on each cut or move start
+    CameraCommands
+}
+
+impl CaptureMode {
+    pub const ALL: [Self; 2] = [Self::EveryFrame, Self::CameraCommands];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::EveryFrame => "Every Frame",
+            Self::CameraCommands => "On CSD_/CMD_",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CapturedNode {
+    pub(crate) time: f32,
+    pub(crate) frame: i32,
+    // carries the event camera's FOV
+    pub(crate) node: FreecamNode,
+}
+
+#[derive(Debug)]
+pub struct CaptureState {
+    pub(crate) mode: CaptureMode,
+    pub(crate) recording: bool,
+    pub(crate) elapsed: f32,
+    pub(crate) samples: Vec<CapturedNode>,
+    // previous frame, kept as the end of a shot when a camera command starts the next one
+    last_frame: Option<CapturedNode>,
+}
+
+impl Default for CaptureState {
+    fn default() -> Self {
+        Self {
+            mode: CaptureMode::EveryFrame,
+            recording: false,
+            elapsed: 0.,
+            samples: vec![],
+            last_frame: None,
+        }
+    }
+}
+
+impl CaptureState {
+    pub(crate) fn is_watching_commands(&self) -> bool {
+        self.recording && self.mode == CaptureMode::CameraCommands
+    }
+}
+
+impl Freecam {
+    pub(crate) fn start_event_capture(&mut self) {
+        self.capture.samples.clear();
+        self.capture.last_frame = None;
+        self.capture.elapsed = 0.;
+        self.capture.recording = true;
+        EVT_CAMERA_COMMAND.store(false, Ordering::Relaxed);
+        logln!(Verbose, "Started capturing event camera ({})", self.capture.mode.get_name());
+    }
+
+    pub(crate) fn stop_event_capture(&mut self) {
+        self.capture.recording = false;
+        // end of the last shot
+        if let Some(last) = self.capture.last_frame.take() {
+            if self.capture.samples.last().map_or(false, |s| s.time < last.time) {
+                self.capture.samples.push(last);
+            }
+        }
+        logln!(Verbose, "Captured {} event camera samples ({:.02} sec)", self.capture.samples.len(), self.capture.elapsed);
+    }
+
+    pub(crate) fn capture_event_camera(&mut self, cam: &GfdCamera, frame: i32, delta: f32) {
+        CAPTURE_ON_COMMAND.store(self.capture.is_watching_commands(), Ordering::Relaxed);
+        // the event camera only holds the freecam view while freecam is on
+        if !self.capture.recording || self.flags.contains(FreecamFlags::ACTIVE) { return; }
+        let take = match self.capture.mode {
+            CaptureMode::EveryFrame => true,
+            CaptureMode::CameraCommands => EVT_CAMERA_COMMAND.swap(false, Ordering::Relaxed) || self.capture.samples.is_empty()
+        };
+        if !self.capture.samples.is_empty() { self.capture.elapsed += delta; }
+        let sample = CapturedNode {
+            time: self.capture.elapsed,
+            frame,
+            node: FreecamNode::from_view_transform(cam.get_view_transform()).with_fovy(cam.get_fovy())
+        };
+        if take {
+            // hold the previous shot up to the frame before the command so the change stays a cut
+            if let Some(last) = self.capture.last_frame.take() {
+                if self.capture.samples.last().map_or(false, |s| s.time < last.time) {
+                    self.capture.samples.push(last);
+                }
+            }
+            self.capture.samples.push(sample);
+        } else {
+            self.capture.last_frame = Some(sample);
+        }
+    }
+
+    // nodes keep the captured times, so uneven spacing and cuts play back as recorded
+    pub(crate) fn apply_event_capture(&mut self) {
+        let (first, last) = match (self.capture.samples.first(), self.capture.samples.last()) {
+            (Some(f), Some(l)) => (f.clone(), l.clone()),
+            _ => return
+        };
+        self.nodes = self.capture.samples.iter()
+            .map(|s| s.node.clone().with_time(s.time - first.time))
+            .collect();
+        self.fit_path_time_to_nodes();
+        self.node_path_current = 0.;
+        self.clear_node_selection();
+        logln!(Verbose, "Created {} nodes from event frames {}-{} ({:.02} sec)", self.nodes.len(), first.frame, last.frame, last.time - first.time);
+    }
+
+    pub(crate) fn draw_contents_capture(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Event Capture##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        let mut mode = CaptureMode::ALL.iter().position(|m| *m == self.capture.mode).unwrap();
+        ui.set_next_item_width(max_width / 3.);
+        ui.disabled(self.capture.recording, || {
+            if ui.combo("Sample##ForFreecamCapture", &mut mode, &CaptureMode::ALL, |m| m.get_name().into()) {
+                self.capture.mode = CaptureMode::ALL[mode];
+            }
+        });
+        ui.same_line_with_spacing(0., 10.);
+        match self.capture.recording {
+            true => if ui.button("Stop##ForFreecamCapture") { self.stop_event_capture(); },
+            false => if ui.button("Record##ForFreecamCapture") { self.start_event_capture(); }
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.disabled(self.capture.recording || self.capture.samples.is_empty(), || {
+            if ui.button("Apply to Path##ForFreecamCapture") { self.apply_event_capture(); }
+        });
+        ui.same_line_with_spacing(0., 10.);
+        ui.disabled(self.capture.recording, || {
+            if ui.button("Clear##ForFreecamCapture") { self.capture.samples.clear(); }
+        });
+        match (self.capture.samples.first(), self.capture.samples.last()) {
+            (Some(first), Some(last)) => ui.text(format!("{} samples, frames {}-{} ({:.02} sec)",
+                self.capture.samples.len(), first.frame, last.frame, last.time - first.time)),
+            _ => ui.text_disabled("Nothing captured")
+        }
+        if self.capture.recording && self.flags.contains(FreecamFlags::ACTIVE) {
+            ui.text_disabled("Paused while freecam is active");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use imgui::Ui;
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::object::camera::Camera as GfdCamera;
+use rkyv::{Archive, Deserialize, Serialize};
+use xrd744_lib::btl::camera::CameraController;
+use crate::state::camera::{Freecam, FreecamFlags};
+
+pub(crate) const CLIP_DEFAULT_NEAR: f32 = 10.;
+pub(crate) const CLIP_DEFAULT_FAR: f32 = 100000.;
+pub(crate) const CLIP_MIN_NEAR: f32 = 0.01;
+
+// time is in seconds along the camera path
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct ClipKeyframe {
+    pub(crate) time: f32,
+    pub(crate) near: f32,
+    pub(crate) far: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct ClipSettings {
+    pub(crate) enabled: bool,
+    pub(crate) near: f32,
+    pub(crate) far: f32,
+    pub(crate) use_keyframes: bool,
+    // sorted by time
+    pub(crate) keyframes: Vec<ClipKeyframe>,
+}
+
+impl Default for ClipSettings {
+    fn default() -> Self {
+        Self { enabled: false, near: CLIP_DEFAULT_NEAR, far: CLIP_DEFAULT_FAR, use_keyframes: false, keyframes: vec![] }
+    }
+}
+
+// the game's own near/far from before the override, restored once it stops applying
+#[derive(Debug, Default)]
+pub struct ClipRestore {
+    // keyed by camera address, only used to match a camera against its saved planes
+    cameras: Vec<(usize, f32, f32)>,
+    battle: Option<(f32, f32)>,
+}
+
+impl ClipSettings {
+    fn sample(&self, time: f32) -> (f32, f32) {
+        let keys = &self.keyframes;
+        let next = keys.iter().position(|k| k.time > time);
+        match next {
+            _ if keys.is_empty() => (self.near, self.far),
+            Some(0) => (keys[0].near, keys[0].far),
+            None => (keys[keys.len() - 1].near, keys[keys.len() - 1].far),
+            Some(i) => {
+                let (a, b) = (&keys[i - 1], &keys[i]);
+                let t = (time - a.time) / (b.time - a.time).max(f32::EPSILON);
+                (a.near + (b.near - a.near) * t, a.far + (b.far - a.far) * t)
+            }
+        }
+    }
+}
+
+impl Freecam {
+    // clip planes to use this frame, or None to leave the game's own values alone
+    pub(crate) fn get_clip_planes(&self) -> Option<(f32, f32)> {
+        if !self.clip.enabled { return None; }
+        match self.clip.use_keyframes && !self.clip.keyframes.is_empty() {
+            true => Some(self.clip.sample(self.node_path_current)),
+            false => Some((self.clip.near, self.clip.far))
+        }
+    }
+
+    pub(crate) fn apply_clip_planes(&mut self, cam: &mut GfdCamera) {
+        let (near, far) = match self.get_clip_planes() {
+            Some(v) => v,
+            None => return self.restore_clip_planes(cam)
+        };
+        let addr = &raw const *cam as usize;
+        if !self.clip_restore.cameras.iter().any(|c| c.0 == addr) {
+            self.clip_restore.cameras.push((addr, cam.get_near_clip(), cam.get_far_clip()));
+        }
+        cam.set_near_clip(near);
+        cam.set_far_clip(far);
+    }
+
+    pub(crate) fn restore_clip_planes(&mut self, cam: &mut GfdCamera) {
+        let addr = &raw const *cam as usize;
+        if let Some(i) = self.clip_restore.cameras.iter().position(|c| c.0 == addr) {
+            let (_, near, far) = self.clip_restore.cameras.swap_remove(i);
+            cam.set_near_clip(near);
+            cam.set_far_clip(far);
+        }
+    }
+
+    pub(crate) fn apply_battle_clip_planes(&mut self, ctrl: &mut CameraController<GfdAllocator>) {
+        let (near, far) = match self.get_clip_planes() {
+            Some(v) => v,
+            None => return self.restore_battle_clip_planes(ctrl)
+        };
+        if self.clip_restore.battle.is_none() {
+            self.clip_restore.battle = Some((ctrl.get_current_near_clip(), ctrl.get_current_far_clip()));
+        }
+        ctrl.set_current_near_clip(near);
+        ctrl.set_current_far_clip(far);
+    }
+
+    pub(crate) fn restore_battle_clip_planes(&mut self, ctrl: &mut CameraController<GfdAllocator>) {
+        if let Some((near, far)) = self.clip_restore.battle.take() {
+            ctrl.set_current_near_clip(near);
+            ctrl.set_current_far_clip(far);
+        }
+    }
+
+    // event and battle cameras are restored from their own hooks once freecam stops driving them
+    pub(crate) fn restore_scene_clip_planes(&mut self) {
+        if self.clip_restore.cameras.is_empty() { return; }
+        if let Some(cam) = Self::get_scene_camera_mut() { self.restore_clip_planes(cam); }
+    }
+
+    // anything left over belongs to a camera that has since gone away
+    pub(crate) fn reset_clip_restore(&mut self) {
+        self.clip_restore = ClipRestore::default();
+    }
+
+    fn add_clip_keyframe(&mut self) {
+        let key = ClipKeyframe { time: self.node_path_current, near: self.clip.near, far: self.clip.far };
+        match self.clip.keyframes.iter().position(|k| k.time >= key.time) {
+            Some(i) if self.clip.keyframes[i].time == key.time => self.clip.keyframes[i] = key,
+            Some(i) => self.clip.keyframes.insert(i, key),
+            None => self.clip.keyframes.push(key)
+        }
+    }
+
+    pub(crate) fn draw_contents_clip(&mut self, ui: &Ui) {
+        let max_width = ui.content_region_avail()[0];
+        ui.checkbox("Clip##ForFreecamWindow", &mut self.clip.enabled);
+        ui.disabled(!self.clip.enabled, || {
+            // show the keyframed value while a path is playing
+            let playing = self.clip.use_keyframes && self.flags.contains(FreecamFlags::PLAYING_PATH);
+            let (mut near, mut far) = match playing {
+                true => self.get_clip_planes().unwrap_or((self.clip.near, self.clip.far)),
+                false => (self.clip.near, self.clip.far)
+            };
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(max_width / 5.);
+            if ui.input_float("Near##ForFreecamClip", &mut near).display_format("%.2f").build() {
+                self.clip.near = near.max(CLIP_MIN_NEAR);
+            }
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(max_width / 5.);
+            if ui.input_float("Far##ForFreecamClip", &mut far).display_format("%.0f").build() {
+                self.clip.far = far.max(self.clip.near + CLIP_MIN_NEAR);
+            }
+            ui.same_line_with_spacing(0., 10.);
+            ui.checkbox("Keyframes##ForFreecamClip", &mut self.clip.use_keyframes);
+            ui.disabled(!self.clip.use_keyframes, || {
+                ui.same_line_with_spacing(0., 10.);
+                if ui.button(format!("Add Key ({})##ForFreecamClip", self.clip.keyframes.len())) {
+                    self.add_clip_keyframe();
+                }
+                if ui.is_item_hovered() {
+                    ui.tooltip_text("Store the current near/far at the current path time");
+                }
+                ui.same_line_with_spacing(0., 10.);
+                if ui.button("Clear Keys##ForFreecamClip") { self.clip.keyframes.clear(); }
+            });
+        });
+    }
+}
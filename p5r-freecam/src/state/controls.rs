@@ -52,6 +52,9 @@ impl Freecam {
                 }
             }
         }
+        self.draw_contents_time_control(ui);
+        ui.same_line_with_spacing(0., 10.);
+        self.draw_contents_clip(ui);
         /*
         let time_length = "Time".chars().map(|c| font_data.get_glyph(c).advance_x).sum::<f32>();
         ui.set_next_item_width((max_width / 2.) - (time_length + 10.));
@@ -80,5 +83,9 @@ impl Freecam {
         if ui.button(play_pause) { self.toggle_playback().unwrap(); }
         ui.same_line_with_spacing(0., 10.);
         if ui.button("Stop##ForFreecamWindow") { self.stop_playback().unwrap(); }
+        self.draw_contents_orientation(ui);
+        ui.same_line_with_spacing(0., 10.);
+        self.draw_contents_smoothing(ui);
+        self.draw_contents_horizon(ui);
     }
 }
\ No newline at end of file
@@ -0,0 +1,117 @@
+use glam::{Vec3, Vec3A};
+use imgui::Ui;
+use riri_mod_tools_rt::logln;
+use crate::state::camera::{Freecam, FREECAM_MOVE_SPEED};
+use crate::state::node::FreecamNode;
+use crate::state::orbit::CameraMode;
+
+pub(crate) const DOLLY_DEFAULT_DISTANCE: f32 = 300.;
+pub(crate) const DOLLY_MIN_FOVY: f32 = 5.;
+pub(crate) const DOLLY_MAX_FOVY: f32 = 175.;
+// distance changes by e^(DOLLY_SPEED) per second at full stick deflection
+pub(crate) const DOLLY_SPEED: f32 = 1.;
+pub(crate) const DOLLY_RECORD_NODES: usize = 8;
+
+#[derive(Debug)]
+pub struct DollyState {
+    pub(crate) subject_distance: f32,
+    pub(crate) end_fovy: f32,
+    subject: Vec3A,
+    // half of the frame width at the subject, kept constant while zooming
+    frame_width: f32,
+}
+
+impl Default for DollyState {
+    fn default() -> Self {
+        Self { subject_distance: DOLLY_DEFAULT_DISTANCE, end_fovy: 90., subject: Vec3A::ZERO, frame_width: 0. }
+    }
+}
+
+impl Freecam {
+    // lock the subject in front of the camera at the current FOV
+    pub(crate) fn begin_dolly_zoom(&mut self) -> bool {
+        let fovy = match Self::get_scene_camera() {
+            Some(c) => c.get_fovy(),
+            None => {
+                logln!(Verbose, "No scene camera to dolly zoom with");
+                return false;
+            }
+        };
+        self.dolly.subject = self.camera_pos - Self::get_back_vector(self.pan, self.pitch) * self.dolly.subject_distance;
+        self.dolly.frame_width = self.dolly.subject_distance * (fovy.to_radians() / 2.).tan();
+        true
+    }
+
+    fn get_dolly_distance(&self, fovy: f32) -> f32 {
+        self.dolly.frame_width / (fovy.to_radians() / 2.).tan()
+    }
+
+    fn get_dolly_fovy(&self, distance: f32) -> f32 {
+        (2. * (self.dolly.frame_width / distance).atan()).to_degrees()
+    }
+
+    pub(crate) fn set_dolly_fovy(&mut self, fovy: f32) {
+        let fovy = fovy.clamp(DOLLY_MIN_FOVY, DOLLY_MAX_FOVY);
+        let distance = self.get_dolly_distance(fovy);
+        self.camera_pos = self.dolly.subject + Self::get_back_vector(self.pan, self.pitch) * distance;
+        if let Some(cam) = Self::get_scene_camera_mut() { cam.set_fovy(fovy); }
+    }
+
+    // forward/back input trades distance for FOV, everything else is ignored so the subject stays put
+    pub(crate) fn apply_dolly_motion(&mut self, move_delta: Vec3) {
+        let distance = (self.camera_pos - self.dolly.subject).length().max(f32::EPSILON);
+        let distance = distance * (move_delta.z / FREECAM_MOVE_SPEED * DOLLY_SPEED).exp();
+        self.set_dolly_fovy(self.get_dolly_fovy(distance));
+    }
+
+    // keep the subject framed while a recorded dolly path plays back
+    pub(crate) fn update_dolly_fovy_from_position(&mut self) {
+        let distance = (self.camera_pos - self.dolly.subject).length().max(f32::EPSILON);
+        let fovy = self.get_dolly_fovy(distance);
+        if let Some(cam) = Self::get_scene_camera_mut() { cam.set_fovy(fovy); }
+    }
+
+    // replace the path with a straight move from the current FOV to the end FOV.
+    // nodes carry their FOV so the path plays back without the dolly subject
+    pub(crate) fn record_dolly_path(&mut self) {
+        let start = match Self::get_scene_camera() {
+            Some(c) => c.get_fovy(),
+            None => return
+        };
+        let end = self.dolly.end_fovy.clamp(DOLLY_MIN_FOVY, DOLLY_MAX_FOVY);
+        let back = Self::get_back_vector(self.pan, self.pitch);
+        self.nodes = (0..DOLLY_RECORD_NODES).map(|i| {
+            let fovy = start + (end - start) * i as f32 / (DOLLY_RECORD_NODES - 1) as f32;
+            let trans = self.dolly.subject + back * self.get_dolly_distance(fovy);
+            FreecamNode::new_euler(trans, self.pan, self.pitch, self.roll).with_fovy(fovy)
+        }).collect();
+        self.clear_node_selection();
+        self.node_path_current = 0.;
+        logln!(Verbose, "Recorded dolly zoom from {:.02} to {:.02} FOV", start, end);
+    }
+
+    pub(crate) fn draw_contents_dolly(&mut self, ui: &Ui) {
+        let max_width = ui.content_region_avail()[0];
+        let active = self.camera_mode == CameraMode::Dolly;
+        ui.disabled(active, || {
+            ui.set_next_item_width(max_width / 5.);
+            if ui.input_float("Subject Distance##ForFreecamDolly", &mut self.dolly.subject_distance).display_format("%.1f").build() {
+                self.dolly.subject_distance = self.dolly.subject_distance.max(1.);
+            }
+        });
+        ui.disabled(!active, || {
+            ui.same_line_with_spacing(0., 10.);
+            if let Some(mut fovy) = Self::get_scene_camera().map(|c| c.get_fovy()) {
+                ui.set_next_item_width(max_width / 4.);
+                if ui.slider_config("Dolly FOV##ForFreecamDolly", DOLLY_MIN_FOVY, DOLLY_MAX_FOVY).build(&mut fovy) {
+                    self.set_dolly_fovy(fovy);
+                }
+            }
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(max_width / 8.);
+            ui.input_float("End FOV##ForFreecamDolly", &mut self.dolly.end_fovy).display_format("%.1f").build();
+            ui.same_line_with_spacing(0., 10.);
+            if ui.button("Record Path##ForFreecamDolly") { self.record_dolly_path(); }
+        });
+    }
+}
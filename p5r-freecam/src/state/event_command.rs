@@ -0,0 +1,170 @@
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use imgui::{TreeNodeFlags, Ui};
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use xrd744_lib::evt::function_table::EVT_COMMAND_COUNT;
+use crate::state::camera::{Freecam, FreecamFlags};
+use crate::state::capture::is_camera_command;
+
+#[derive(Debug)]
+pub struct EvtCommandInfo {
+    pub(crate) name: String,
+    pub(crate) execute: usize,
+    pub(crate) struct_size: u32,
+    // hook slot shared by every command with the same execute function
+    pub(crate) slot: Option<usize>,
+}
+
+// commands that run through one execute function
+#[derive(Debug)]
+pub struct EvtHookSlot {
+    pub(crate) execute: usize,
+    pub(crate) commands: Vec<usize>,
+}
+
+static EVT_COMMANDS: OnceLock<Vec<EvtCommandInfo>> = OnceLock::new();
+static EVT_HOOK_SLOTS: OnceLock<Vec<EvtHookSlot>> = OnceLock::new();
+// one bit per hook slot
+static EVT_SLOT_SUPPRESSED: [AtomicU64; 2] = [AtomicU64::new(0), AtomicU64::new(0)];
+
+pub(crate) fn set_evt_commands(mut commands: Vec<EvtCommandInfo>) {
+    let mut slots: Vec<EvtHookSlot> = vec![];
+    for (i, command) in commands.iter_mut().enumerate() {
+        if command.execute == 0 { continue; }
+        let slot = match slots.iter().position(|s| s.execute == command.execute) {
+            Some(v) => v,
+            None => {
+                slots.push(EvtHookSlot { execute: command.execute, commands: vec![] });
+                slots.len() - 1
+            }
+        };
+        slots[slot].commands.push(i);
+        command.slot = Some(slot);
+    }
+    let _ = EVT_COMMANDS.set(commands);
+    let _ = EVT_HOOK_SLOTS.set(slots);
+}
+
+pub(crate) fn get_evt_commands() -> &'static [EvtCommandInfo] {
+    EVT_COMMANDS.get().map_or(&[], |v| v.as_slice())
+}
+
+pub(crate) fn get_evt_hook_slots() -> &'static [EvtHookSlot] {
+    EVT_HOOK_SLOTS.get().map_or(&[], |v| v.as_slice())
+}
+
+pub(crate) fn get_evt_slot_commands(slot: usize) -> impl Iterator<Item = &'static EvtCommandInfo> {
+    let commands = get_evt_commands();
+    get_evt_hook_slots().get(slot).into_iter().flat_map(move |s| s.commands.iter().filter_map(move |i| commands.get(*i)))
+}
+
+pub(crate) fn is_evt_slot_suppressed(slot: usize) -> bool {
+    EVT_SLOT_SUPPRESSED[slot / u64::BITS as usize].load(Ordering::Relaxed) & (1 << (slot % u64::BITS as usize)) != 0
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct EvtCommandSettings {
+    // command names to skip while freecam is active
+    pub(crate) suppressed: Vec<String>,
+}
+
+impl Default for EvtCommandSettings {
+    fn default() -> Self {
+        Self {
+            suppressed: ["CMD_", "CSA_", "CSD_", "CShk", "CSF_"].iter().map(|s| s.to_string()).collect()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct EvtCommandState {
+    pub(crate) settings: EvtCommandSettings,
+    hooked: [bool; EVT_COMMAND_COUNT],
+    filter: String,
+}
+
+impl Default for EvtCommandState {
+    fn default() -> Self {
+        Self {
+            settings: EvtCommandSettings::default(),
+            hooked: [false; EVT_COMMAND_COUNT],
+            filter: String::new(),
+        }
+    }
+}
+
+impl EvtCommandState {
+    // hooks are keyed by execute function so shared handlers only get detoured once
+    pub(crate) fn hook_command(&mut self, index: usize) {
+        let slot = match get_evt_commands().get(index).and_then(|c| c.slot) {
+            Some(v) => v, None => return
+        };
+        if self.hooked[slot] { return; }
+        let execute = get_evt_hook_slots()[slot].execute;
+        crate::hooks::event_command::hook_evt_command(slot, execute);
+        let names: Vec<&str> = get_evt_slot_commands(slot).map(|c| c.name.as_str()).collect();
+        logln!(Verbose, "Created hook for event command {}: 0x{:x}", names.join("/"), execute);
+        self.hooked[slot] = true;
+    }
+}
+
+impl Freecam {
+    pub(crate) fn update_evt_commands(&mut self) {
+        let active = self.flags.contains(FreecamFlags::ACTIVE);
+        // tracing needs every command hooked
+        let trace = self.evt_trace.enabled;
+        let capture = self.capture.is_watching_commands();
+        let mut suppressed = [0u64; 2];
+        for (i, command) in get_evt_commands().iter().enumerate() {
+            let suppress = self.evt_commands.settings.suppressed.contains(&command.name);
+            if !suppress && !trace && !(capture && is_camera_command(&command.name)) { continue; }
+            // hook as soon as a command is picked so the first call after entering freecam is caught
+            self.evt_commands.hook_command(i);
+            // a shared execute function is skipped if any command using it is suppressed
+            if let (true, true, Some(slot)) = (suppress, active, command.slot) {
+                suppressed[slot / u64::BITS as usize] |= 1 << (slot % u64::BITS as usize);
+            }
+        }
+        for (bits, value) in EVT_SLOT_SUPPRESSED.iter().zip(suppressed) {
+            bits.store(value, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn draw_contents_evt_commands(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Event Commands##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let commands = get_evt_commands();
+        if commands.is_empty() {
+            ui.text_disabled("Event command table hasn't been found");
+            return;
+        }
+        ui.text("Suppress while freecam is active");
+        ui.set_next_item_width(ui.content_region_avail()[0] / 3.);
+        ui.input_text("##FilterForFreecamEvtCommand", &mut self.evt_commands.filter).hint("Filter").build();
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Clear##ForFreecamEvtCommand") {
+            self.evt_commands.settings.suppressed.clear();
+        }
+        let filter = self.evt_commands.filter.trim().to_lowercase();
+        let suppressed = &mut self.evt_commands.settings.suppressed;
+        ui.child_window("##ListForFreecamEvtCommand").size([0., 200.]).build(|| {
+            for (i, command) in commands.iter().enumerate() {
+                if !filter.is_empty() && !command.name.to_lowercase().contains(&filter) { continue; }
+                let mut selected = suppressed.contains(&command.name);
+                if ui.checkbox(format!("{}##{}ForFreecamEvtCommand", command.name, i), &mut selected) {
+                    match selected {
+                        true => suppressed.push(command.name.clone()),
+                        false => suppressed.retain(|n| *n != command.name)
+                    }
+                }
+                // these can't be told apart once hooked, so suppressing one suppresses all of them
+                let shared: Vec<&str> = command.slot.into_iter().flat_map(get_evt_slot_commands)
+                    .filter(|c| c.name != command.name).map(|c| c.name.as_str()).collect();
+                if !shared.is_empty() {
+                    ui.same_line_with_spacing(0., 10.);
+                    ui.text_disabled(format!("(shares handler with {})", shared.join(", ")));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use imgui::{TreeNodeFlags, Ui};
+use riri_inspector_components::table::{InspectorTable, TableDraw};
+use windows::Win32::System::Memory::{VirtualQuery, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_GUARD, PAGE_NOACCESS};
+use crate::state::camera::Freecam;
+use crate::state::event_command::get_evt_slot_commands;
+
+// oldest entries are dropped past this
+const EVT_TRACE_MAX_ENTRIES: usize = 4096;
+// struct_size comes from the game's table, don't trust it to be small
+const EVT_TRACE_MAX_PARAM_SIZE: usize = 0x100;
+
+static EVT_TRACE_ENABLED: AtomicBool = AtomicBool::new(false);
+static EVT_CURRENT_FRAME: AtomicI32 = AtomicI32::new(0);
+// filled by the command hooks, drained into EvtTraceState once per frame
+static EVT_TRACE_PENDING: Mutex<VecDeque<EvtTraceEntry>> = Mutex::new(VecDeque::new());
+
+pub(crate) fn set_evt_frame(frame: i32) {
+    EVT_CURRENT_FRAME.store(frame, Ordering::Relaxed);
+}
+
+// how many bytes from ptr can be read without leaving committed, readable memory
+unsafe fn get_readable_len(ptr: *const u8, len: usize) -> usize {
+    if ptr.is_null() || len == 0 { return 0; }
+    let mut info = MEMORY_BASIC_INFORMATION::default();
+    if VirtualQuery(Some(ptr as *const c_void), &mut info, size_of::<MEMORY_BASIC_INFORMATION>()) == 0 { return 0; }
+    if info.State != MEM_COMMIT || info.Protect.0 & (PAGE_NOACCESS.0 | PAGE_GUARD.0) != 0 { return 0; }
+    let region_end = info.BaseAddress as usize + info.RegionSize;
+    len.min(region_end.saturating_sub(ptr as usize))
+}
+
+pub(crate) unsafe fn trace_evt_command(slot: usize, p_work: *mut u8) {
+    if !EVT_TRACE_ENABLED.load(Ordering::Relaxed) { return; }
+    let size = get_evt_slot_commands(slot).map(|c| c.struct_size as usize).min().unwrap_or(0);
+    let readable = get_readable_len(p_work, size.min(EVT_TRACE_MAX_PARAM_SIZE));
+    let params = match readable {
+        0 => vec![],
+        n => std::slice::from_raw_parts(p_work, n).to_vec()
+    };
+    let entry = EvtTraceEntry { slot, frame: EVT_CURRENT_FRAME.load(Ordering::Relaxed), size, params };
+    if let Ok(mut pending) = EVT_TRACE_PENDING.lock() {
+        // nothing drains this while the freecam task isn't running
+        if pending.len() >= EVT_TRACE_MAX_ENTRIES { pending.pop_front(); }
+        pending.push_back(entry);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EvtTraceEntry {
+    pub(crate) slot: usize,
+    pub(crate) frame: i32,
+    // struct_size from the table, params may be shorter if it couldn't all be read
+    pub(crate) size: usize,
+    pub(crate) params: Vec<u8>,
+}
+
+impl EvtTraceEntry {
+    // commands sharing an execute function can't be told apart
+    pub(crate) fn get_name(&self) -> String {
+        get_evt_slot_commands(self.slot).map(|c| c.name.as_str()).collect::<Vec<_>>().join("/")
+    }
+
+    pub(crate) fn get_params_text(&self) -> String {
+        let mut out = String::with_capacity(self.params.len() * 3);
+        for (i, b) in self.params.iter().enumerate() {
+            if i > 0 { out.push(' '); }
+            let _ = write!(out, "{:02X}", b);
+        }
+        if self.params.len() < self.size {
+            let _ = write!(out, " (+{} bytes not read)", self.size - self.params.len());
+        }
+        out
+    }
+}
+
+impl TableDraw<Freecam> for EvtTraceEntry {
+    fn draw_contents(&self, ui: &Ui, _: &mut Freecam, index: usize) {
+        match index {
+            0 => ui.text(format!("{}", self.frame)),
+            1 => ui.text(self.get_name()),
+            2 => ui.text_wrapped(self.get_params_text()),
+            _ => ()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EvtTraceState {
+    pub(crate) enabled: bool,
+    pub(crate) entries: VecDeque<EvtTraceEntry>,
+    filter: String,
+}
+
+impl Freecam {
+    pub(crate) fn update_evt_trace(&mut self) {
+        EVT_TRACE_ENABLED.store(self.evt_trace.enabled, Ordering::Relaxed);
+        let pending = match EVT_TRACE_PENDING.lock() {
+            Ok(mut v) => std::mem::take(&mut *v),
+            Err(_) => return
+        };
+        let entries = &mut self.evt_trace.entries;
+        entries.extend(pending);
+        while entries.len() > EVT_TRACE_MAX_ENTRIES { entries.pop_front(); }
+    }
+
+    pub(crate) fn draw_contents_evt_trace(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Event Trace##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        ui.checkbox("Trace Commands##ForFreecamEvtTrace", &mut self.evt_trace.enabled);
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Clear##ForFreecamEvtTrace") { self.evt_trace.entries.clear(); }
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(ui.content_region_avail()[0] / 2.);
+        ui.input_text("##FilterForFreecamEvtTrace", &mut self.evt_trace.filter).hint("Filter by command").build();
+        // filter on command names, several can be given separated by commas
+        let filters: Vec<String> = self.evt_trace.filter.split(',')
+            .map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty()).collect();
+        let contents: Vec<EvtTraceEntry> = self.evt_trace.entries.iter()
+            .filter(|e| filters.is_empty() || filters.iter().any(|f| e.get_name().to_lowercase().contains(f)))
+            .cloned().collect();
+        ui.text_disabled(format!("{} / {} commands", contents.len(), self.evt_trace.entries.len()));
+        let mut table: InspectorTable<EvtTraceEntry, Self, 3> = InspectorTable::new(
+            "Event Trace", Some([ "Frame", "Command", "Parameters" ]),
+            riri_inspector_components::table::default_flags(),
+            300.,
+        );
+        table.draw_table(ui, self, &contents);
+    }
+}
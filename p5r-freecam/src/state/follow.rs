@@ -0,0 +1,249 @@
+use std::f32::consts::{PI, TAU};
+use glam::{EulerRot, Mat4, Quat, Vec3, Vec3A};
+use imgui::Ui;
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::kernel::graphics::GraphicsGlobal;
+use opengfd::kernel::task::Task as GfdTask;
+use opengfd::object::node::Node as GfdNode;
+use riri_mod_tools_rt::logln;
+use xrd744_lib::btl::package::Package;
+use crate::state::camera::Freecam;
+use crate::state::orbit::CameraMode;
+use crate::state::smoothing::{smooth_damp, SMOOTHING_MAX_TIME};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FollowTarget {
+    FieldTarget,
+    // first party member in battle, otherwise whoever the field camera tracks
+    Player,
+    SceneNode,
+    BattleUnit
+}
+
+impl FollowTarget {
+    pub const ALL: [Self; 4] = [ Self::FieldTarget, Self::Player, Self::SceneNode, Self::BattleUnit ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::FieldTarget => "Field Camera Target",
+            Self::Player => "Player",
+            Self::SceneNode => "Scene Node",
+            Self::BattleUnit => "Battle Unit"
+        }
+    }
+}
+
+// depth first through the current scene's node tree
+fn find_scene_node<'a>(node: &'a GfdNode, name: &str) -> Option<&'a GfdNode> {
+    let mut next = Some(node);
+    while let Some(node) = next {
+        if node.get_name() == Some(name) { return Some(node); }
+        if let Some(found) = node.get_child().and_then(|c| find_scene_node(c, name)) { return Some(found); }
+        next = node.get_sibling();
+    }
+    None
+}
+
+fn collect_scene_node_names(node: &GfdNode, out: &mut Vec<String>) {
+    let mut next = Some(node);
+    while let Some(node) = next {
+        if let Some(name) = node.get_name().filter(|n| !n.is_empty()) { out.push(name.to_owned()); }
+        if let Some(child) = node.get_child() { collect_scene_node_names(child, out); }
+        next = node.get_sibling();
+    }
+}
+
+fn get_scene_root() -> Option<&'static GfdNode> {
+    GraphicsGlobal::get_gfd_graphics_global().get_current_scene()
+        .and_then(|scn| scn.get_root_node())
+}
+
+// party members first, then enemies
+fn get_battle_unit_nodes() -> Vec<(String, &'static GfdNode)> {
+    let pkg = match GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle")
+        .and_then(|task| task.get_main_work()) {
+        Some(v) => v, None => return vec![]
+    };
+    let players = pkg.get_player_units().enumerate().map(|(i, u)| (format!("Party {}", i + 1), u));
+    let enemies = pkg.get_enemy_units().enumerate().map(|(i, u)| (format!("Enemy {}", i + 1), u));
+    players.chain(enemies).filter_map(|(name, u)| u.get_node().map(|n| (name, n))).collect()
+}
+
+#[derive(Debug)]
+pub struct FollowState {
+    pub(crate) target: FollowTarget,
+    pub(crate) follow_yaw: bool,
+    pub(crate) smoothing: bool,
+    pub(crate) smooth_time: f32,
+    pub(crate) node_name: String,
+    pub(crate) unit: usize,
+    // refreshed while the target list is open
+    node_names: Vec<String>,
+    // camera pose relative to the target, rotated by the target's yaw when follow_yaw is set
+    offset: Vec3A,
+    pan_offset: f32,
+    anchor_pos: Vec3A,
+    anchor_yaw: f32,
+    pos_velocity: Vec3A,
+    yaw_velocity: f32,
+}
+
+impl Default for FollowState {
+    fn default() -> Self {
+        Self {
+            target: FollowTarget::FieldTarget,
+            follow_yaw: false,
+            smoothing: true,
+            smooth_time: 0.2,
+            node_name: String::new(),
+            unit: 0,
+            node_names: vec![],
+            offset: Vec3A::ZERO,
+            pan_offset: 0.,
+            anchor_pos: Vec3A::ZERO,
+            anchor_yaw: 0.,
+            pos_velocity: Vec3A::ZERO,
+            yaw_velocity: 0.,
+        }
+    }
+}
+
+// shortest signed difference between two angles
+fn wrap_angle(v: f32) -> f32 {
+    (v + PI).rem_euclid(TAU) - PI
+}
+
+impl Freecam {
+    fn get_node_transform(node: &GfdNode) -> (Vec3A, f32) {
+        let (_, rot, trans) = Mat4::from(node.get_world_transform()).to_scale_rotation_translation();
+        (trans.into(), rot.to_euler(EulerRot::YXZEx).0)
+    }
+
+    fn get_follow_transform(&self) -> Option<(Vec3A, f32)> {
+        let node = match self.follow.target {
+            FollowTarget::FieldTarget => Self::get_field_camera().and_then(|c| c.get_target_node()),
+            FollowTarget::Player => match get_battle_unit_nodes().first() {
+                Some((_, n)) => Some(*n),
+                None => Self::get_field_camera().and_then(|c| c.get_target_node())
+            },
+            FollowTarget::SceneNode => get_scene_root().and_then(|r| find_scene_node(r, &self.follow.node_name)),
+            FollowTarget::BattleUnit => get_battle_unit_nodes().get(self.follow.unit).map(|(_, n)| *n)
+        };
+        node.map(Self::get_node_transform)
+    }
+
+    fn get_follow_yaw(&self) -> f32 {
+        match self.follow.follow_yaw {
+            true => self.follow.anchor_yaw,
+            false => 0.
+        }
+    }
+
+    // keep the current pose relative to wherever the target is now
+    pub(crate) fn attach_follow_target(&mut self) -> bool {
+        let (pos, yaw) = match self.get_follow_transform() {
+            Some(v) => v,
+            None => {
+                logln!(Verbose, "No {} to follow", self.follow.target.get_name());
+                return false;
+            }
+        };
+        self.follow.anchor_pos = pos;
+        self.follow.anchor_yaw = yaw;
+        self.follow.pos_velocity = Vec3A::ZERO;
+        self.follow.yaw_velocity = 0.;
+        let yaw = self.get_follow_yaw();
+        self.follow.offset = Quat::from_rotation_y(-yaw) * (self.camera_pos - pos);
+        self.follow.pan_offset = self.pan - yaw;
+        true
+    }
+
+    pub(crate) fn apply_follow_motion(&mut self, rot_delta: Vec3, move_delta: Vec3, delta: f32) {
+        let (pos, yaw) = match self.get_follow_transform() {
+            Some(v) => v,
+            // target went away (e.g. map change), stay where we are
+            None => return self.apply_manual_motion(rot_delta, move_delta, delta)
+        };
+        let follow = &mut self.follow;
+        if follow.smoothing {
+            follow.anchor_pos = smooth_damp(follow.anchor_pos, pos, &mut follow.pos_velocity, follow.smooth_time, delta);
+            let target_yaw = follow.anchor_yaw + wrap_angle(yaw - follow.anchor_yaw);
+            follow.anchor_yaw = smooth_damp(follow.anchor_yaw, target_yaw, &mut follow.yaw_velocity, follow.smooth_time, delta);
+        } else {
+            follow.anchor_pos = pos;
+            follow.anchor_yaw = yaw;
+        }
+        let yaw = self.get_follow_yaw();
+        // inputs adjust the relative pose
+        self.follow.pan_offset += rot_delta.x;
+        self.pitch += rot_delta.y;
+        self.roll += rot_delta.z;
+        self.pan = yaw + self.follow.pan_offset;
+        self.follow.offset += Quat::from_rotation_y(-yaw) * Self::get_move_vector(self.pan, self.pitch, move_delta);
+        self.camera_pos = self.follow.anchor_pos + Quat::from_rotation_y(yaw) * self.follow.offset;
+    }
+
+    pub(crate) fn draw_contents_follow(&mut self, ui: &Ui) {
+        let max_width = ui.content_region_avail()[0];
+        let mut target = FollowTarget::ALL.iter().position(|t| *t == self.follow.target).unwrap();
+        ui.set_next_item_width(max_width / 4.);
+        if ui.combo("Follow Target##ForFreecamFollow", &mut target, &FollowTarget::ALL, |t| t.get_name().into()) {
+            self.follow.target = FollowTarget::ALL[target];
+            if self.camera_mode == CameraMode::Follow { self.set_camera_mode(CameraMode::Follow); }
+        }
+        let changed = match self.follow.target {
+            FollowTarget::SceneNode => self.draw_follow_scene_node(ui, max_width),
+            FollowTarget::BattleUnit => self.draw_follow_battle_unit(ui, max_width),
+            _ => false
+        };
+        if changed && self.camera_mode == CameraMode::Follow { self.set_camera_mode(CameraMode::Follow); }
+        ui.same_line_with_spacing(0., 10.);
+        if ui.checkbox("Follow Yaw##ForFreecamFollow", &mut self.follow.follow_yaw) {
+            if self.camera_mode == CameraMode::Follow { self.attach_follow_target(); }
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.checkbox("Smooth##ForFreecamFollow", &mut self.follow.smoothing);
+        ui.disabled(!self.follow.smoothing, || {
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(ui.content_region_avail()[0]);
+            ui.slider_config("##SmoothTimeForFreecamFollow", 0., SMOOTHING_MAX_TIME)
+                .display_format("%.2f sec").build(&mut self.follow.smooth_time);
+        });
+    }
+
+    fn draw_follow_scene_node(&mut self, ui: &Ui, max_width: f32) -> bool {
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(max_width / 4.);
+        let preview = match self.follow.node_name.is_empty() {
+            true => "(none)",
+            false => self.follow.node_name.as_str()
+        };
+        let mut changed = false;
+        if let Some(_combo) = ui.begin_combo("##NodeForFreecamFollow", preview) {
+            if ui.is_window_appearing() {
+                self.follow.node_names.clear();
+                if let Some(root) = get_scene_root() { collect_scene_node_names(root, &mut self.follow.node_names); }
+            }
+            for (i, name) in self.follow.node_names.iter().enumerate() {
+                if ui.selectable_config(format!("{}##{}ForFreecamFollow", name, i))
+                    .selected(*name == self.follow.node_name).build() {
+                    self.follow.node_name = name.clone();
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    fn draw_follow_battle_unit(&mut self, ui: &Ui, max_width: f32) -> bool {
+        ui.same_line_with_spacing(0., 10.);
+        let units = get_battle_unit_nodes();
+        if units.is_empty() {
+            ui.text_disabled("Not in battle");
+            return false;
+        }
+        self.follow.unit = self.follow.unit.min(units.len() - 1);
+        ui.set_next_item_width(max_width / 4.);
+        ui.combo("##UnitForFreecamFollow", &mut self.follow.unit, &units, |(name, _)| name.as_str().into())
+    }
+}
@@ -0,0 +1,123 @@
+use std::f32::consts::FRAC_PI_2;
+use imgui::{ImColor32, Ui};
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_H;
+use crate::state::camera::Freecam;
+
+pub(crate) const HORIZON_DEFAULT_PITCH_LIMIT: f32 = 89.;
+pub(crate) const HORIZON_DEFAULT_LEVEL_TIME: f32 = 0.25;
+const ROLL_INDICATOR_SIZE: f32 = 48.;
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct HorizonSettings {
+    pub(crate) clamp_pitch: bool,
+    // degrees above or below the horizon
+    pub(crate) pitch_limit: f32,
+    pub(crate) level_pitch: bool,
+    pub(crate) level_time: f32,
+}
+
+impl Default for HorizonSettings {
+    fn default() -> Self {
+        Self {
+            clamp_pitch: false,
+            pitch_limit: HORIZON_DEFAULT_PITCH_LIMIT,
+            level_pitch: false,
+            level_time: HORIZON_DEFAULT_LEVEL_TIME
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LevelBlend {
+    from_roll: f32,
+    from_pitch: f32,
+    time: f32,
+}
+
+impl Freecam {
+    pub(crate) fn clamp_pitch(&self, pitch: f32) -> f32 {
+        match self.horizon.clamp_pitch {
+            true => {
+                let limit = self.horizon.pitch_limit.to_radians().min(FRAC_PI_2);
+                pitch.clamp(-limit, limit)
+            },
+            false => pitch
+        }
+    }
+
+    pub(crate) fn level_horizon(&mut self) {
+        // take the shortest way back to level
+        let wrap = |v: f32| (v + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+        self.level_blend = Some(LevelBlend { from_roll: wrap(self.roll), from_pitch: self.pitch, time: 0. });
+        logln!(Verbose, "Levelling horizon");
+    }
+
+    pub fn update_level_horizon(&mut self) {
+        if Self::check_key_pressed(VK_H) { self.level_horizon(); }
+    }
+
+    pub(crate) fn update_level_blend(&mut self, delta: f32) {
+        let blend = match self.level_blend.as_mut() {
+            Some(v) => v,
+            None => return
+        };
+        blend.time += delta;
+        let t = match self.horizon.level_time > 0. {
+            true => (blend.time / self.horizon.level_time).min(1.),
+            false => 1.
+        };
+        let t = t * t * (3. - 2. * t);
+        self.roll = blend.from_roll * (1. - t);
+        if self.horizon.level_pitch {
+            self.pitch = blend.from_pitch * (1. - t);
+        }
+        if t >= 1. { self.level_blend = None; }
+        // don't let inertia pull the camera back to where it was
+        self.reset_smoothing();
+    }
+
+    fn draw_roll_indicator(&self, ui: &Ui) {
+        let pos = ui.cursor_screen_pos();
+        let radius = ROLL_INDICATOR_SIZE / 2.;
+        let center = [pos[0] + radius, pos[1] + radius];
+        let (sin, cos) = self.roll.sin_cos();
+        let draw_list = ui.get_window_draw_list();
+        draw_list.add_circle(center, radius - 1., ImColor32::from_rgb(128, 128, 128)).build();
+        draw_list.add_line(
+            [center[0] - cos * radius, center[1] - sin * radius],
+            [center[0] + cos * radius, center[1] + sin * radius],
+            ImColor32::from_rgb(255, 200, 64)
+        ).thickness(2.).build();
+        ui.dummy([ROLL_INDICATOR_SIZE, ROLL_INDICATOR_SIZE]);
+    }
+
+    pub(crate) fn draw_contents_horizon(&mut self, ui: &Ui) {
+        self.draw_roll_indicator(ui);
+        ui.same_line_with_spacing(0., 10.);
+        ui.group(|| {
+            let width = ui.content_region_avail()[0] / 4.;
+            ui.text(format!("Roll: {:.1}°  Pitch: {:.1}°", self.roll.to_degrees(), self.pitch.to_degrees()));
+            ui.same_line_with_spacing(0., 10.);
+            if ui.button("Level Horizon##ForFreecamWindow") { self.level_horizon(); }
+            ui.same_line_with_spacing(0., 10.);
+            ui.checkbox("Level Pitch##ForFreecamHorizon", &mut self.horizon.level_pitch);
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(width / 2.);
+            if ui.input_float("##LevelTimeForFreecamHorizon", &mut self.horizon.level_time).display_format("%.2f sec").build() {
+                self.horizon.level_time = self.horizon.level_time.max(0.);
+            }
+            ui.checkbox("Limit Pitch (Euler)##ForFreecamHorizon", &mut self.horizon.clamp_pitch);
+            if ui.is_item_hovered() {
+                ui.tooltip_text("Only applies to Euler rotation, Quaternion fly rotates freely");
+            }
+            ui.disabled(!self.horizon.clamp_pitch, || {
+                ui.same_line_with_spacing(0., 10.);
+                ui.set_next_item_width(width);
+                ui.slider_config("##PitchLimitForFreecamHorizon", 0., 90.)
+                    .display_format("%.1f°").build(&mut self.horizon.pitch_limit);
+            });
+        });
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
+use imgui::{TreeNodeFlags, Ui};
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::kernel::task::Task as GfdTask;
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_F6;
+use crate::hooks::field::HUD_HOOK_SLOT_COUNT;
+use crate::state::camera::{Freecam, FreecamFlags};
+
+// one bit per hook slot in hooks/field.rs
+static HUD_HIDDEN_SLOTS: AtomicU32 = AtomicU32::new(0);
+
+pub(crate) fn is_hud_slot_hidden(slot: usize) -> bool {
+    HUD_HIDDEN_SLOTS.load(Ordering::Relaxed) & (1 << slot) != 0
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct HudElement {
+    pub(crate) task_name: String,
+    // hidden along with the rest of the HUD
+    pub(crate) enabled: bool,
+    // hidden regardless of freecam or the HUD toggle
+    pub(crate) always_hidden: bool,
+}
+
+impl HudElement {
+    fn new(task_name: &str) -> Self {
+        Self { task_name: task_name.to_owned(), enabled: true, always_hidden: false }
+    }
+
+    fn is_hidden(&self, hud_off: bool) -> bool {
+        self.always_hidden || (self.enabled && hud_off)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct HudSettings {
+    pub(crate) hide_with_freecam: bool,
+    pub(crate) elements: Vec<HudElement>,
+}
+
+impl Default for HudSettings {
+    fn default() -> Self {
+        Self {
+            hide_with_freecam: true,
+            elements: vec![
+                HudElement::new("fld_panel"),
+                HudElement::new("date draw"),
+                HudElement::new("draw mission list(FLD)"),
+                HudElement::new("btlPartyPanel"),
+                HudElement::new("road map(FLD)"),
+                HudElement::new("fldPanelTipUpdate"),
+            ]
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct HudState {
+    pub(crate) settings: HudSettings,
+    // hides the HUD without needing freecam
+    pub(crate) hidden: bool,
+    // update function hooked by each slot, tasks sharing an update function share a slot
+    hooked: Vec<usize>,
+    slots: HashMap<String, usize>,
+    // tasks that couldn't be hooked because every slot was taken
+    unhooked: HashSet<String>,
+    new_task_name: String,
+}
+
+impl HudState {
+    fn hook_task(&mut self, task_name: &str) -> Option<usize> {
+        let task = GfdTask::<GfdAllocator, ()>::find_by_str_mut(task_name)?;
+        let update_fn = task.get_update_ptr() as usize;
+        let slot = match self.hooked.iter().position(|f| *f == update_fn) {
+            Some(v) => v,
+            None => {
+                if self.hooked.len() >= HUD_HOOK_SLOT_COUNT {
+                    if self.unhooked.insert(task_name.to_owned()) {
+                        logln!(Verbose, "No HUD hook slots left for {}", task_name);
+                    }
+                    return None;
+                }
+                let slot = self.hooked.len();
+                crate::hooks::field::hook_hud_slot(slot, update_fn);
+                self.hooked.push(update_fn);
+                logln!(Verbose, "Hooked {} update: 0x{:x} (slot {})", task_name, update_fn, slot);
+                slot
+            }
+        };
+        self.slots.insert(task_name.to_owned(), slot);
+        Some(slot)
+    }
+
+    fn get_slot_status(&self, task_name: &str) -> String {
+        match self.slots.get(task_name) {
+            Some(slot) => {
+                // hiding a task also hides every other task with the same update function
+                let mut shared: Vec<&str> = self.slots.iter()
+                    .filter(|(k, v)| *v == slot && k.as_str() != task_name)
+                    .map(|(k, _)| k.as_str()).collect();
+                shared.sort();
+                match shared.is_empty() {
+                    true => "(hooked)".to_owned(),
+                    false => format!("(shares hook with {})", shared.join(", "))
+                }
+            },
+            None => match self.unhooked.contains(task_name) {
+                true => "(no hook slots left)".to_owned(),
+                false => "(not loaded)".to_owned()
+            }
+        }
+    }
+}
+
+impl Freecam {
+    pub(crate) fn toggle_hud(&mut self) {
+        self.hud.hidden = !self.hud.hidden;
+        self.mark_session_dirty();
+        logln!(Verbose, "HUD {}", if self.hud.hidden { "hidden" } else { "shown" });
+    }
+
+    pub(crate) fn is_hud_off(&self) -> bool {
+        self.hud.hidden || (self.hud.settings.hide_with_freecam && self.flags.contains(FreecamFlags::ACTIVE))
+    }
+
+    pub(crate) fn update_ui_element_visibility(&mut self) {
+        if Self::check_key_pressed(VK_F6) {
+            self.toggle_hud();
+        }
+        let hud_off = self.is_hud_off();
+        // tasks only get hooked once they exist and need hiding
+        let mut hidden = 0;
+        for i in 0..self.hud.settings.elements.len() {
+            let element = &self.hud.settings.elements[i];
+            if !element.is_hidden(hud_off) { continue; }
+            let slot = match self.hud.slots.get(&element.task_name).copied() {
+                Some(v) => Some(v),
+                None if self.hud.unhooked.contains(&element.task_name) => None,
+                None => {
+                    let name = element.task_name.clone();
+                    self.hud.hook_task(&name)
+                }
+            };
+            if let Some(slot) = slot { hidden |= 1 << slot; }
+        }
+        HUD_HIDDEN_SLOTS.store(hidden, Ordering::Relaxed);
+    }
+
+    pub(crate) fn draw_contents_hud(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("HUD##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let mut hidden = self.hud.hidden;
+        if ui.checkbox("Hide HUD (F6)##ForFreecamHud", &mut hidden) {
+            self.toggle_hud();
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.checkbox("Hide with freecam##ForFreecamHud", &mut self.hud.settings.hide_with_freecam);
+        let mut remove = None;
+        let statuses: Vec<String> = self.hud.settings.elements.iter()
+            .map(|e| self.hud.get_slot_status(&e.task_name)).collect();
+        for (i, (element, status)) in self.hud.settings.elements.iter_mut().zip(statuses).enumerate() {
+            ui.checkbox(format!("{}##{}ForFreecamHud", element.task_name, i), &mut element.enabled);
+            ui.same_line_with_spacing(0., 10.);
+            ui.checkbox(format!("Always hide##{}ForFreecamHud", i), &mut element.always_hidden);
+            ui.same_line_with_spacing(0., 10.);
+            ui.text_disabled(status);
+            ui.same_line_with_spacing(0., 10.);
+            if ui.small_button(format!("Remove##{}ForFreecamHud", i)) { remove = Some(i); }
+        }
+        if let Some(i) = remove { self.hud.settings.elements.remove(i); }
+
+        ui.set_next_item_width(ui.content_region_avail()[0] / 3.);
+        ui.input_text("##NewTaskNameForFreecamHud", &mut self.hud.new_task_name).hint("Task name").build();
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Add##ForFreecamHud") {
+            let name = self.hud.new_task_name.trim();
+            if !name.is_empty() && !self.hud.settings.elements.iter().any(|e| e.task_name == name) {
+                self.hud.settings.elements.push(HudElement::new(name));
+                self.hud.new_task_name.clear();
+            }
+        }
+    }
+}
@@ -0,0 +1,535 @@
+use std::error::Error;
+use std::fmt::Write;
+use glam::{EulerRot, Quat, Vec3, Vec3A};
+use imgui::{TreeNodeFlags, Ui};
+use riri_file_dialog::dialog::{FileDialogManager, FileTypeFilter, OpenDialog, SaveDialog};
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use crate::gui::utils::AppError;
+use crate::state::camera::Freecam;
+use crate::state::node::FreecamNode;
+
+const MAYA_CHAN_FILE_EXT: &'static str = "chan";
+const AE_KEYFRAME_FILE_EXT: &'static str = "txt";
+const AE_KEYFRAME_HEADER: &'static str = "Adobe After Effects 8.0 Keyframe Data";
+const AE_KEYFRAME_FOOTER: &'static str = "End of Keyframe Data";
+
+pub(crate) const INTERCHANGE_DEFAULT_FRAME_RATE: f32 = 30.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+pub enum InterchangeFormat {
+    MayaChan,
+    AfterEffects
+}
+
+impl InterchangeFormat {
+    pub const ALL: [Self; 2] = [ Self::MayaChan, Self::AfterEffects ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::MayaChan => "Maya .chan",
+            Self::AfterEffects => "After Effects Keyframes"
+        }
+    }
+
+    fn get_filter(&self) -> FileTypeFilter {
+        match self {
+            Self::MayaChan => FileTypeFilter::new(MAYA_CHAN_FILE_EXT.to_owned(), "Maya Channel File".to_owned()),
+            Self::AfterEffects => FileTypeFilter::new(AE_KEYFRAME_FILE_EXT.to_owned(), "After Effects Keyframe Data".to_owned()),
+        }
+    }
+}
+
+// Rotation orders are named in the order the axes are applied, matching Maya's rotateOrder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+pub enum RotationOrder {
+    Xyz,
+    Yzx,
+    Zxy,
+    Xzy,
+    Yxz,
+    Zyx
+}
+
+impl RotationOrder {
+    pub const ALL: [Self; 6] = [ Self::Xyz, Self::Yzx, Self::Zxy, Self::Xzy, Self::Yxz, Self::Zyx ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Xyz => "XYZ",
+            Self::Yzx => "YZX",
+            Self::Zxy => "ZXY",
+            Self::Xzy => "XZY",
+            Self::Yxz => "YXZ",
+            Self::Zyx => "ZYX"
+        }
+    }
+
+    fn get_euler_rot(&self) -> EulerRot {
+        match self {
+            Self::Xyz => EulerRot::XYZEx,
+            Self::Yzx => EulerRot::YZXEx,
+            Self::Zxy => EulerRot::ZXYEx,
+            Self::Xzy => EulerRot::XZYEx,
+            Self::Yxz => EulerRot::YXZEx,
+            Self::Zyx => EulerRot::ZYXEx
+        }
+    }
+
+    // glam returns angles in application order, so shuffle them back into X, Y, Z
+    pub fn to_xyz_degrees(&self, rot: Quat) -> Vec3 {
+        let (a, b, c) = rot.to_euler(self.get_euler_rot());
+        let xyz = match self {
+            Self::Xyz => Vec3::new(a, b, c),
+            Self::Yzx => Vec3::new(c, a, b),
+            Self::Zxy => Vec3::new(b, c, a),
+            Self::Xzy => Vec3::new(a, c, b),
+            Self::Yxz => Vec3::new(b, a, c),
+            Self::Zyx => Vec3::new(c, b, a)
+        };
+        Vec3::new(xyz.x.to_degrees(), xyz.y.to_degrees(), xyz.z.to_degrees())
+    }
+
+    pub fn from_xyz_degrees(&self, xyz: Vec3) -> Quat {
+        let (x, y, z) = (xyz.x.to_radians(), xyz.y.to_radians(), xyz.z.to_radians());
+        let (a, b, c) = match self {
+            Self::Xyz => (x, y, z),
+            Self::Yzx => (y, z, x),
+            Self::Zxy => (z, x, y),
+            Self::Xzy => (x, z, y),
+            Self::Yxz => (y, x, z),
+            Self::Zyx => (z, y, x)
+        };
+        Quat::from_euler(self.get_euler_rot(), a, b, c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct InterchangeSettings {
+    pub(crate) format: InterchangeFormat,
+    pub(crate) frame_rate: f32,
+    pub(crate) rotation_order: RotationOrder,
+    // game units per exported unit
+    pub(crate) scale: f32,
+    pub(crate) comp_width: u32,
+    pub(crate) comp_height: u32,
+}
+
+impl Default for InterchangeSettings {
+    fn default() -> Self {
+        Self {
+            format: InterchangeFormat::MayaChan,
+            frame_rate: INTERCHANGE_DEFAULT_FRAME_RATE,
+            rotation_order: RotationOrder::Xyz,
+            scale: 1.,
+            comp_width: 1920,
+            comp_height: 1080
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InterchangeKeyframe {
+    frame: f32,
+    node: FreecamNode,
+    fovy: Option<f32>
+}
+
+#[derive(Debug)]
+pub struct InterchangeTrack {
+    keyframes: Vec<InterchangeKeyframe>,
+    frame_rate: f32,
+    // comp size stored in the file, if it has one
+    comp_size: Option<(u32, u32)>,
+}
+
+impl InterchangeTrack {
+    // keep each key's time so uneven spacing survives the import
+    pub fn into_nodes(self) -> Vec<FreecamNode> {
+        let first = self.keyframes.first().map_or(0., |k| k.frame);
+        let frame_rate = self.frame_rate;
+        self.keyframes.into_iter().map(|k| {
+            let node = k.node.with_time((k.frame - first) / frame_rate);
+            match k.fovy {
+                Some(fovy) => node.with_fovy(fovy),
+                None => node
+            }
+        }).collect()
+    }
+}
+
+impl InterchangeSettings {
+    fn get_frame(&self, time: f32) -> f32 {
+        time * self.frame_rate
+    }
+
+    fn parse_float(value: &str, line: usize) -> Result<f32, Box<dyn Error>> {
+        value.parse::<f32>().map_err(|e|
+            Box::new(AppError::new_owned(format!("Invalid number \"{}\" on line {}: {}", value, line + 1, e))) as Box<dyn Error>)
+    }
+
+    // frame tx ty tz rx ry rz fov. times are in seconds per node, fovy is used for nodes without their own
+    pub fn export_maya_chan(&self, nodes: &[FreecamNode], times: &[f32], fovy: f32) -> String {
+        let mut out = String::new();
+        for (i, node) in nodes.iter().enumerate() {
+            let trans = node.trans / self.scale;
+            let rot = self.rotation_order.to_xyz_degrees(node.get_camera_rotation());
+            let _ = writeln!(out, "{} {:.6} {:.6} {:.6} {:.6} {:.6} {:.6} {:.6}",
+                self.get_frame(times[i]), trans.x, trans.y, trans.z, rot.x, rot.y, rot.z, node.fovy.unwrap_or(fovy));
+        }
+        out
+    }
+
+    pub fn import_maya_chan(&self, text: &str) -> Result<InterchangeTrack, Box<dyn Error>> {
+        let mut keyframes = vec![];
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+            let values = line.split_whitespace()
+                .map(|v| Self::parse_float(v, i))
+                .collect::<Result<Vec<_>, _>>()?;
+            if values.len() < 7 {
+                return Err(Box::new(AppError::new_owned(format!("Expected at least 7 columns on line {}, got {}", i + 1, values.len()))));
+            }
+            let trans = Vec3A::new(values[1], values[2], values[3]) * self.scale;
+            let rot = self.rotation_order.from_xyz_degrees(Vec3::new(values[4], values[5], values[6]));
+            keyframes.push(InterchangeKeyframe {
+                frame: values[0],
+                node: FreecamNode::from_camera_transform(trans, rot),
+                fovy: values.get(7).copied()
+            });
+        }
+        Ok(InterchangeTrack { keyframes, frame_rate: self.frame_rate, comp_size: None })
+    }
+
+    // After Effects is Y down with the camera looking down +Z, which is a half turn around X from our space
+    fn to_ae_space(&self, trans: Vec3A) -> Vec3 {
+        let trans = trans / self.scale;
+        Vec3::new(trans.x + self.comp_width as f32 / 2., -trans.y + self.comp_height as f32 / 2., -trans.z)
+    }
+
+    fn from_ae_space(&self, trans: Vec3) -> Vec3A {
+        Vec3A::new(trans.x - self.comp_width as f32 / 2., -(trans.y - self.comp_height as f32 / 2.), -trans.z) * self.scale
+    }
+
+    fn get_ae_zoom(&self, fovy: f32) -> f32 {
+        (self.comp_height as f32 / 2.) / (fovy.to_radians() / 2.).tan()
+    }
+
+    fn get_ae_fovy(&self, zoom: f32) -> f32 {
+        ((self.comp_height as f32 / 2.) / zoom).atan().to_degrees() * 2.
+    }
+
+    pub fn export_after_effects(&self, nodes: &[FreecamNode], times: &[f32], fovy: f32) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "{}\n", AE_KEYFRAME_HEADER);
+        let _ = writeln!(out, "\tUnits Per Second\t{}", self.frame_rate);
+        let _ = writeln!(out, "\tSource Width\t{}", self.comp_width);
+        let _ = writeln!(out, "\tSource Height\t{}", self.comp_height);
+        let _ = writeln!(out, "\tSource Pixel Aspect Ratio\t1");
+        let _ = writeln!(out, "\tComp Pixel Aspect Ratio\t1\n");
+        let _ = writeln!(out, "Transform\tPosition");
+        let _ = writeln!(out, "\tFrame\tX pixels\tY pixels\tZ pixels\t");
+        for (i, node) in nodes.iter().enumerate() {
+            let trans = self.to_ae_space(node.trans);
+            let _ = writeln!(out, "\t{}\t{:.6}\t{:.6}\t{:.6}\t", self.get_frame(times[i]), trans.x, trans.y, trans.z);
+        }
+        let _ = writeln!(out, "\nTransform\tOrientation");
+        let _ = writeln!(out, "\tFrame\tX degrees\tY degrees\tZ degrees\t");
+        for (i, node) in nodes.iter().enumerate() {
+            let rot = self.rotation_order.to_xyz_degrees(node.get_camera_rotation());
+            let _ = writeln!(out, "\t{}\t{:.6}\t{:.6}\t{:.6}\t", self.get_frame(times[i]), rot.x, -rot.y, -rot.z);
+        }
+        let _ = writeln!(out, "\nCamera Options\tZoom");
+        let _ = writeln!(out, "\tFrame\tpixels\t");
+        match nodes.iter().any(|n| n.fovy.is_some()) {
+            true => for (i, node) in nodes.iter().enumerate() {
+                let _ = writeln!(out, "\t{}\t{:.6}\t", self.get_frame(times[i]), self.get_ae_zoom(node.fovy.unwrap_or(fovy)));
+            },
+            false => { let _ = writeln!(out, "\t0\t{:.6}\t", self.get_ae_zoom(fovy)); }
+        }
+        let _ = writeln!(out, "\n\n{}", AE_KEYFRAME_FOOTER);
+        out
+    }
+
+    pub fn import_after_effects(&self, text: &str) -> Result<InterchangeTrack, Box<dyn Error>> {
+        if !text.trim_start().starts_with("Adobe After Effects") {
+            return Err(Box::new(AppError::new("Text is not After Effects keyframe data")));
+        }
+        let mut frame_rate = self.frame_rate;
+        let (mut comp_width, mut comp_height) = (None, None);
+        let mut positions: Vec<(f32, Vec3)> = vec![];
+        let mut orientations: Vec<(f32, Vec3)> = vec![];
+        let mut zooms: Vec<(f32, f32)> = vec![];
+        let mut section = "";
+        for (i, line) in text.lines().enumerate() {
+            let columns: Vec<&str> = line.split('\t').map(|c| c.trim()).filter(|c| !c.is_empty()).collect();
+            if columns.is_empty() { continue; }
+            if !line.starts_with('\t') {
+                section = match columns.get(1) {
+                    Some(&"Position") => "Position",
+                    Some(&"Orientation") => "Orientation",
+                    Some(&"Zoom") => "Zoom",
+                    _ => ""
+                };
+                continue;
+            }
+            if columns[0] == "Units Per Second" {
+                frame_rate = Self::parse_float(columns.get(1).copied().unwrap_or(""), i)?.max(1.);
+                continue;
+            }
+            // prefer the comp size, camera layers only have a source size matching it
+            let size_value = |c: &[&str]| Self::parse_float(c.get(1).copied().unwrap_or(""), i).map(|v| v.max(1.) as u32);
+            match columns[0] {
+                "Comp Width" => { comp_width = Some(size_value(&columns)?); continue; },
+                "Comp Height" => { comp_height = Some(size_value(&columns)?); continue; },
+                "Source Width" => { comp_width = comp_width.or(Some(size_value(&columns)?)); continue; },
+                "Source Height" => { comp_height = comp_height.or(Some(size_value(&columns)?)); continue; },
+                _ => ()
+            }
+            // skip column headers and any sections we don't use
+            if columns[0] == "Frame" || section.is_empty() { continue; }
+            let values = columns.iter()
+                .map(|v| Self::parse_float(v, i))
+                .collect::<Result<Vec<_>, _>>()?;
+            match (section, values.len()) {
+                ("Position", 4..) => positions.push((values[0], Vec3::new(values[1], values[2], values[3]))),
+                ("Orientation", 4..) => orientations.push((values[0], Vec3::new(values[1], values[2], values[3]))),
+                ("Zoom", 2..) => zooms.push((values[0], values[1])),
+                _ => return Err(Box::new(AppError::new_owned(format!("Not enough columns on line {}", i + 1))))
+            }
+        }
+        if positions.is_empty() {
+            return Err(Box::new(AppError::new("Keyframe data has no camera position")));
+        }
+        // positions and zoom are in comp pixels, so convert with the file's comp size
+        let comp_size = comp_width.zip(comp_height);
+        let space = match comp_size {
+            Some((comp_width, comp_height)) => Self { comp_width, comp_height, ..self.clone() },
+            None => self.clone()
+        };
+        // use the latest orientation/zoom keyed at or before each position key
+        let find_latest = |frame: f32, keys: &[(f32, Vec3)]| keys.iter()
+            .take_while(|(f, _)| *f <= frame).last().or(keys.first()).map(|(_, v)| *v);
+        let keyframes = positions.iter().map(|(frame, pos)| {
+            let rot = find_latest(*frame, &orientations).unwrap_or(Vec3::ZERO);
+            let rot = self.rotation_order.from_xyz_degrees(Vec3::new(rot.x, -rot.y, -rot.z));
+            let fovy = zooms.iter().take_while(|(f, _)| *f <= *frame).last().or(zooms.first())
+                .map(|(_, z)| space.get_ae_fovy(*z));
+            InterchangeKeyframe {
+                frame: *frame,
+                node: FreecamNode::from_camera_transform(space.from_ae_space(*pos), rot),
+                fovy
+            }
+        }).collect();
+        Ok(InterchangeTrack { keyframes, frame_rate, comp_size })
+    }
+}
+
+impl Freecam {
+    fn get_interchange_fovy() -> f32 {
+        Self::get_scene_camera().map_or(45., |c| c.get_fovy())
+    }
+
+    pub(crate) fn export_interchange(&self) -> String {
+        let fovy = Self::get_interchange_fovy();
+        // key each node where playback reaches it, so captured and imported timing is kept
+        let times: Vec<f32> = (0..self.nodes.len()).map(|i| self.get_node_path_time(i)).collect();
+        match self.interchange.format {
+            InterchangeFormat::MayaChan => self.interchange.export_maya_chan(&self.nodes, &times, fovy),
+            InterchangeFormat::AfterEffects => self.interchange.export_after_effects(&self.nodes, &times, fovy),
+        }
+    }
+
+    pub(crate) fn import_interchange(&mut self, text: &str) -> Result<(), Box<dyn Error>> {
+        let track = match self.interchange.format {
+            InterchangeFormat::MayaChan => self.interchange.import_maya_chan(text)?,
+            InterchangeFormat::AfterEffects => self.interchange.import_after_effects(text)?,
+        };
+        if let Some((width, height)) = track.comp_size {
+            self.interchange.comp_width = width;
+            self.interchange.comp_height = height;
+        }
+        self.interchange.frame_rate = track.frame_rate;
+        self.nodes = track.into_nodes();
+        self.fit_path_time_to_nodes();
+        self.node_path_current = 0.;
+        self.clear_node_selection();
+        self.mark_session_dirty();
+        logln!(Verbose, "Imported {} nodes from {} ({:.02} sec)", self.nodes.len(), self.interchange.format.get_name(), self.node_path_time);
+        Ok(())
+    }
+
+    pub(crate) fn draw_contents_interchange(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Import/Export##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        let mut format = InterchangeFormat::ALL.iter().position(|f| *f == self.interchange.format).unwrap();
+        ui.set_next_item_width(max_width / 3.);
+        if ui.combo("Format##ForFreecamInterchange", &mut format, &InterchangeFormat::ALL, |f| f.get_name().into()) {
+            self.interchange.format = InterchangeFormat::ALL[format];
+        }
+        ui.same_line_with_spacing(0., 10.);
+        let mut order = RotationOrder::ALL.iter().position(|o| *o == self.interchange.rotation_order).unwrap();
+        ui.set_next_item_width(max_width / 6.);
+        if ui.combo("Rotation Order##ForFreecamInterchange", &mut order, &RotationOrder::ALL, |o| o.get_name().into()) {
+            self.interchange.rotation_order = RotationOrder::ALL[order];
+        }
+        ui.set_next_item_width(max_width / 6.);
+        if ui.input_float("Frame Rate##ForFreecamInterchange", &mut self.interchange.frame_rate).display_format("%.3f").build() {
+            self.interchange.frame_rate = self.interchange.frame_rate.max(1.);
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(max_width / 6.);
+        if ui.input_float("Scale##ForFreecamInterchange", &mut self.interchange.scale).display_format("%.3f").build() {
+            self.interchange.scale = self.interchange.scale.max(0.001);
+        }
+        if self.interchange.format == InterchangeFormat::AfterEffects {
+            let mut comp_size = [self.interchange.comp_width as i32, self.interchange.comp_height as i32];
+            ui.set_next_item_width(max_width / 3.);
+            if ui.input_int2("Comp Size##ForFreecamInterchange", &mut comp_size).build() {
+                self.interchange.comp_width = comp_size[0].max(1) as u32;
+                self.interchange.comp_height = comp_size[1].max(1) as u32;
+            }
+        }
+        if ui.button("Import...##ForFreecamInterchange") {
+            let mut dlg_lock = FileDialogManager::get();
+            if let Some(v) = OpenDialog::new(dlg_lock.as_mut().unwrap()).unwrap().open(
+                Some(&[self.interchange.format.get_filter()]),
+                Some("Import camera keyframes")
+            ).unwrap() {
+                match std::fs::read_to_string(v.as_path()) {
+                    Ok(text) => if let Err(e) = self.import_interchange(&text) {
+                        logln!(Verbose, "Error while importing keyframes: {}", e);
+                    },
+                    Err(e) => logln!(Verbose, "Error while opening file: {}", e),
+                }
+            }
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.disabled(self.nodes.is_empty(), || {
+            if ui.button("Export...##ForFreecamInterchange") {
+                let mut dlg_lock = FileDialogManager::get();
+                if let Some(v) = SaveDialog::new(dlg_lock.as_mut().unwrap()).unwrap().save(
+                    Some(&[self.interchange.format.get_filter()]),
+                    Some("Export camera keyframes")
+                ).unwrap() {
+                    match std::fs::write(v.as_path(), self.export_interchange()) {
+                        Ok(_) => logln!(Verbose, "Keyframes exported to {}", v.to_str().unwrap()),
+                        Err(e) => logln!(Verbose, "Couldn't export keyframes: {}", e),
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-3;
+
+    fn test_nodes() -> Vec<FreecamNode> {
+        vec![
+            FreecamNode::new_euler(Vec3A::new(0., 150., -300.), 0.3, -0.2, 0.1).with_fovy(45.),
+            FreecamNode::new_euler(Vec3A::new(120., 160., -250.), 1.2, 0.4, -0.3).with_fovy(30.),
+            FreecamNode::new_euler(Vec3A::new(-80., 90., 40.), -2.1, 0.1, 0.6).with_fovy(60.),
+        ]
+    }
+
+    fn assert_nodes_match(a: &[FreecamNode], b: &[FreecamNode]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            assert!(a.trans.abs_diff_eq(b.trans, EPSILON), "{:?} != {:?}", a.trans, b.trans);
+            // q and -q are the same rotation
+            assert!(a.rot.dot(b.rot).abs() > 1. - EPSILON, "{:?} != {:?}", a.rot, b.rot);
+            match (a.fovy, b.fovy) {
+                (Some(a), Some(b)) => assert!((a - b).abs() < EPSILON, "{} != {}", a, b),
+                (a, b) => assert_eq!(a, b)
+            }
+        }
+    }
+
+    #[test]
+    fn maya_chan_round_trip_all_rotation_orders() {
+        let nodes = test_nodes();
+        for order in RotationOrder::ALL {
+            let settings = InterchangeSettings { rotation_order: order, scale: 2., ..Default::default() };
+            let text = settings.export_maya_chan(&nodes, &[0., 1., 2.], 45.);
+            let imported = settings.import_maya_chan(&text).unwrap().into_nodes();
+            assert_nodes_match(&nodes, &imported);
+        }
+    }
+
+    #[test]
+    fn after_effects_round_trip_all_rotation_orders() {
+        let nodes = test_nodes();
+        for order in RotationOrder::ALL {
+            let settings = InterchangeSettings { format: InterchangeFormat::AfterEffects, rotation_order: order, ..Default::default() };
+            let text = settings.export_after_effects(&nodes, &[0., 1., 2.], 45.);
+            let imported = settings.import_after_effects(&text).unwrap().into_nodes();
+            assert_nodes_match(&nodes, &imported);
+        }
+    }
+
+    #[test]
+    fn after_effects_import_reads_comp_size() {
+        let nodes = test_nodes();
+        let exporter = InterchangeSettings { comp_width: 1280, comp_height: 720, ..Default::default() };
+        let text = exporter.export_after_effects(&nodes, &[0., 1., 2.], 45.);
+        // importing with a different comp size set in the GUI still lands in the same place
+        let track = InterchangeSettings::default().import_after_effects(&text).unwrap();
+        assert_eq!(track.comp_size, Some((1280, 720)));
+        assert_nodes_match(&nodes, &track.into_nodes());
+    }
+
+    #[test]
+    fn import_keeps_key_times() {
+        let nodes = test_nodes();
+        let times = [0., 0.5, 2.];
+        let settings = InterchangeSettings { frame_rate: 24., ..Default::default() };
+        for text in [
+            settings.export_maya_chan(&nodes, &times, 45.),
+            settings.export_after_effects(&nodes, &times, 45.)
+        ] {
+            let track = match text.starts_with(AE_KEYFRAME_HEADER) {
+                true => settings.import_after_effects(&text),
+                false => settings.import_maya_chan(&text)
+            }.unwrap();
+            let imported: Vec<f32> = track.into_nodes().iter().map(|n| n.time.unwrap()).collect();
+            for (a, b) in times.iter().zip(imported) {
+                assert!((a - b).abs() < EPSILON, "{} != {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn after_effects_import_uses_file_frame_rate() {
+        let nodes = test_nodes();
+        let exporter = InterchangeSettings { frame_rate: 60., ..Default::default() };
+        let text = exporter.export_after_effects(&nodes, &[0., 1., 2.], 45.);
+        let track = InterchangeSettings::default().import_after_effects(&text).unwrap();
+        assert_eq!(track.frame_rate, 60.);
+        assert_eq!(track.into_nodes().last().unwrap().time, Some(2.));
+    }
+
+    #[test]
+    fn maya_chan_rejects_malformed_lines() {
+        let settings = InterchangeSettings::default();
+        assert!(settings.import_maya_chan("0 1 2 3 4 5").is_err());
+        assert!(settings.import_maya_chan("0 1 2 three 4 5 6").is_err());
+        assert!(settings.import_maya_chan("# comment\n\n0 1 2 3 4 5 6").is_ok());
+    }
+
+    #[test]
+    fn after_effects_rejects_malformed_data() {
+        let settings = InterchangeSettings::default();
+        assert!(settings.import_after_effects("0 1 2 3 4 5 6").is_err());
+        let short_row = format!("{}\n\nTransform\tPosition\n\tFrame\tX pixels\tY pixels\tZ pixels\t\n\t0\t1\t2\t\n", AE_KEYFRAME_HEADER);
+        assert!(settings.import_after_effects(&short_row).is_err());
+        let bad_number = format!("{}\n\nTransform\tPosition\n\t0\t1\tfoo\t3\t\n", AE_KEYFRAME_HEADER);
+        assert!(settings.import_after_effects(&bad_number).is_err());
+        let no_position = format!("{}\n\nTransform\tOrientation\n\t0\t1\t2\t3\t\n", AE_KEYFRAME_HEADER);
+        assert!(settings.import_after_effects(&no_position).is_err());
+    }
+}
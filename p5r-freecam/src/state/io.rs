@@ -1,15 +1,16 @@
-use glam::Vec3A;
+use glam::{EulerRot, Quat, Vec3A};
 use glam::swizzles::Vec4Swizzles;
 use imgui::Ui;
-use opengfd::kernel::graphics::GraphicsGlobal;
 use riri_file_dialog::dialog::{FileDialogManager, FileTypeFilter, OpenDialog, SaveDialog};
 use riri_mod_tools_rt::logln;
 use rkyv::rancor::ResultExt;
 use rkyv::util::AlignedVec;
 use crate::state::camera::{Freecam, FreecamFlags};
-use crate::state::node::{u32_ne, ArchivedFreecamNode, FreecamNode};
+use crate::state::orbit::CameraMode;
+use crate::state::node::{u32_ne, ArchivedFreecamNode, FreecamNode, FREECAM_NODE_LEGACY_SIZE};
 use rkyv::rancor::Error as RkyvError;
 use crate::gui::app::APP_GLB;
+use crate::gui::utils::AppError;
 
 const FREECAM_FILE_EXT: &'static str = "p5path";
 
@@ -18,6 +19,10 @@ impl Freecam {
         let head = &buf[..size_of::<u32>()];
         let head = rkyv::access::<u32_ne, RkyvError>(head).into_error()?;
         let head = rkyv::deserialize::<u32, RkyvError>(head).into_error()?;
+        let body = &buf[size_of::<u32>()..];
+        if head > 0 && body.len() == head as usize * FREECAM_NODE_LEGACY_SIZE {
+            return Ok(Self::read_legacy(body));
+        }
         let mut nodes = vec![];
         for i in 0..head as usize {
             let start = size_of::<u32>() + (size_of::<ArchivedFreecamNode>() * i);
@@ -28,6 +33,15 @@ impl Freecam {
         Ok(nodes)
     }
 
+    // position and rotation only
+    fn read_legacy(body: &[u8]) -> Vec<FreecamNode> {
+        body.chunks_exact(FREECAM_NODE_LEGACY_SIZE).map(|node| {
+            let v: Vec<f32> = node.chunks_exact(size_of::<f32>())
+                .map(|f| f32::from_ne_bytes(f.try_into().unwrap())).collect();
+            FreecamNode::new(Vec3A::new(v[0], v[1], v[2]), Quat::from_xyzw(v[3], v[4], v[5], v[6]))
+        }).collect()
+    }
+
     fn write(&self) -> Result<AlignedVec, RkyvError> {
         let mut buf = AlignedVec::new();
         let _ = rkyv::util::with_arena(|arena| {
@@ -46,6 +60,43 @@ impl Freecam {
         Ok(buf)
     }
 
+    pub(crate) fn teleport_camera(&mut self, pos: Vec3A, pan: f32, pitch: f32, roll: f32) {
+        (self.camera_pos, self.pan, self.pitch, self.roll) = (pos, pan, pitch, roll);
+        // re-anchor orbit/follow modes around the new pose
+        match self.camera_mode {
+            CameraMode::Fly => self.reset_smoothing(),
+            mode => self.set_camera_mode(mode)
+        }
+    }
+
+    // position and rotation in degrees as "x y z pan pitch roll"
+    pub(crate) fn get_coordinates_text(&self) -> String {
+        format!("{} {} {} {} {} {}", self.camera_pos.x, self.camera_pos.y, self.camera_pos.z,
+            self.pan.to_degrees(), self.pitch.to_degrees(), self.roll.to_degrees())
+    }
+
+    pub(crate) fn set_coordinates_text(&mut self, text: &str) -> Result<(), AppError> {
+        let text = text.trim();
+        // also accept a single copied path node
+        if let Ok(node) = text.parse::<FreecamNode>() {
+            let (pan, pitch, roll) = node.rot.to_euler(EulerRot::YXZEx);
+            self.teleport_camera(node.trans, pan, pitch, roll);
+            return Ok(());
+        }
+        let values = text.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::new_owned(format!("Invalid coordinates \"{}\": {}", text, e)))?;
+        match values.len() {
+            3 => self.teleport_camera(Vec3A::new(values[0], values[1], values[2]), self.pan, self.pitch, self.roll),
+            6 => self.teleport_camera(Vec3A::new(values[0], values[1], values[2]),
+                values[3].to_radians(), values[4].to_radians(), values[5].to_radians()),
+            k => return Err(AppError::new_owned(format!("Expected 3 or 6 values for coordinates, got {}", k)))
+        }
+        Ok(())
+    }
+
     pub(crate) fn draw_contents_topbar(&mut self, ui: &Ui) {
         let max_width = ui.content_region_avail()[0];
         match self.flags.contains(FreecamFlags::ACTIVE) {
@@ -63,7 +114,10 @@ impl Freecam {
                     Ok(buf) => {
                         logln!(Verbose, "Read file {} ({} bytes)", v.as_path().to_str().unwrap(), buf.len());
                         match Self::read(&buf) {
-                            Ok(v) => self.nodes = v,
+                            Ok(v) => {
+                                self.nodes = v;
+                                self.fit_path_time_to_nodes();
+                            },
                             Err(e) => logln!(Verbose, "Error while parsing file: {}", e),
                         }
                     },
@@ -89,25 +143,37 @@ impl Freecam {
         }
         ui.same_line_with_spacing(0., 10.);
         if ui.button("Add Node##ForFreecamWindow") {  self.add_camera_node(); }
-        let scene = GraphicsGlobal::get_gfd_graphics_global_mut();
-        let mut scene_cam = scene.get_scene_mut(0)
-            .and_then(|scn| scn.get_current_camera_mut());
-        let mut cam_pos = scene_cam.as_mut().map_or([0.; 3],
-        |cam| Into::<[f32; 3]>::into(cam.get_view_transform().inverse().w_axis.xyz()));
         let font_data = ui.fonts().get_font(APP_GLB.lock().unwrap().font).unwrap();
         match self.flags.contains(FreecamFlags::ACTIVE) {
             true => {
-                let text_length = "Camera Position".chars().map(|c| font_data.get_glyph(c).advance_x).sum::<f32>();
-                ui.same_line_with_spacing((max_width / 2.) - text_length, 0.);
-                ui.set_next_item_width(max_width / 2.);
-                if ui.input_float3("Camera Position##ForFreecamWindow", &mut cam_pos).build() {
-                    if let Some(_) = scene_cam {
-                        // self.camera_pos = cam_pos.into();
-                        // cam.set_translate(self.camera_pos);
+                let button_width = (max_width / 8.) - 10.;
+                let mut cam_pos: [f32; 3] = self.camera_pos.into();
+                ui.set_next_item_width(max_width / 3.);
+                if ui.input_float3("Position##ForFreecamWindow", &mut cam_pos).display_format("%.2f").build() {
+                    self.teleport_camera(cam_pos.into(), self.pan, self.pitch, self.roll);
+                }
+                ui.same_line_with_spacing(0., 10.);
+                let mut cam_rot = [self.pan.to_degrees(), self.pitch.to_degrees(), self.roll.to_degrees()];
+                ui.set_next_item_width(max_width / 3.);
+                if ui.input_float3("Rotation##ForFreecamWindow", &mut cam_rot).display_format("%.2f").build() {
+                    self.teleport_camera(self.camera_pos, cam_rot[0].to_radians(), cam_rot[1].to_radians(), cam_rot[2].to_radians());
+                }
+                ui.same_line_with_spacing(0., 10.);
+                if ui.button_with_size("Copy##CoordinatesForFreecamWindow", [button_width, 0.]) {
+                    ui.set_clipboard_text(self.get_coordinates_text());
+                }
+                ui.same_line_with_spacing(0., 10.);
+                if ui.button_with_size("Paste##CoordinatesForFreecamWindow", [button_width, 0.]) {
+                    if let Some(text) = ui.clipboard_text() {
+                        if let Err(e) = self.set_coordinates_text(&text) {
+                            logln!(Verbose, "Clipboard doesn't contain coordinates: {}", e);
+                        }
                     }
                 }
             },
             false => {
+                let cam_pos = Self::get_scene_camera().map_or([0.; 3],
+                    |cam| Into::<[f32; 3]>::into(cam.get_view_transform().inverse().w_axis.xyz()));
                 let cam_pos_text = format!("Camera position: {:?}", cam_pos);
                 let cam_length = cam_pos_text.chars().map(|c| font_data.get_glyph(c).advance_x).sum::<f32>();
                 ui.same_line_with_spacing(max_width - cam_length, 0.);
@@ -0,0 +1,137 @@
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicI32, AtomicIsize, Ordering};
+use glam::{Vec2, Vec3};
+use riri_mod_tools_rt::logln;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM};
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, GetFocus, VIRTUAL_KEY,
+    VK_A, VK_CONTROL, VK_D, VK_E, VK_Q, VK_RBUTTON, VK_S, VK_SHIFT, VK_W
+};
+use windows::Win32::UI::WindowsAndMessaging::{CallWindowProcW, GetCursorPos, GetWindowLongPtrW, SetWindowLongPtrW, GWLP_WNDPROC, WHEEL_DELTA, WM_MOUSEWHEEL, WNDPROC};
+use crate::state::camera::{Freecam, FreecamFlags};
+
+// radians turned per pixel of mouse movement
+pub(crate) const KBM_LOOK_SENSITIVITY: f32 = 0.003;
+pub(crate) const KBM_SPEED_STEP: f32 = 1.1;
+pub(crate) const KBM_SPEED_MIN: f32 = 0.05;
+pub(crate) const KBM_SPEED_MAX: f32 = 20.;
+
+static GAME_WND_PROC: AtomicIsize = AtomicIsize::new(0);
+static MOUSE_WHEEL_DELTA: AtomicI32 = AtomicI32::new(0);
+
+// The game doesn't expose the scroll wheel, so subclass its window to catch WM_MOUSEWHEEL
+unsafe extern "system" fn game_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_MOUSEWHEEL {
+        let delta = ((wparam.0 >> u16::BITS as usize) & u16::MAX as usize) as u16 as i16;
+        MOUSE_WHEEL_DELTA.fetch_add(delta as i32, Ordering::Relaxed);
+    }
+    let prev = std::mem::transmute::<isize, WNDPROC>(GAME_WND_PROC.load(Ordering::Relaxed));
+    CallWindowProcW(prev, hwnd, msg, wparam, lparam)
+}
+
+#[derive(Debug)]
+pub struct MouseKeyboardState {
+    pub(crate) speed: f32,
+    last_cursor: Option<POINT>,
+}
+
+impl Default for MouseKeyboardState {
+    fn default() -> Self {
+        Self { speed: 1., last_cursor: None }
+    }
+}
+
+// look is (pan, pitch) in radians, movement is (right, up, forward) in stick deflections
+#[derive(Debug, Default)]
+pub struct MouseKeyboardInput {
+    pub(crate) look: Vec2,
+    pub(crate) movement: Vec3,
+}
+
+impl Freecam {
+    // polled the same way as check_key_pressed. KEYBOARD_INSTANCE only tracks keys, not mouse
+    // buttons or the wheel, and raw input would still need the window subclassed to get WM_INPUT
+    pub fn check_key_held(vk: VIRTUAL_KEY) -> bool {
+        let platform = unsafe { crate::globals::get_platform_global().unwrap() };
+        match unsafe { GetFocus() } == platform.get_hwnd() {
+            true => unsafe { GetAsyncKeyState(vk.0 as i32) as u16 & 0x8000 != 0 },
+            false => false
+        }
+    }
+
+    fn hook_game_window(&mut self) {
+        let platform = unsafe { crate::globals::get_platform_global().unwrap() };
+        let hwnd = platform.get_hwnd();
+        if hwnd.is_invalid() { return; }
+        let prev = unsafe { SetWindowLongPtrW(hwnd, GWLP_WNDPROC, game_wnd_proc as usize as isize) };
+        GAME_WND_PROC.store(prev, Ordering::Relaxed);
+        logln!(Verbose, "Hooked game window procedure: 0x{:x}", prev as usize);
+        self.flags |= FreecamFlags::HOOKED_GAME_WINDOW;
+    }
+
+    // scrolling in menus while freecam was off shouldn't change the move speed on the way in
+    pub(crate) fn reset_mouse_keyboard(&mut self) {
+        MOUSE_WHEEL_DELTA.store(0, Ordering::Relaxed);
+        self.mouse_keyboard.last_cursor = None;
+    }
+
+    pub(crate) fn unhook_game_window(&mut self) {
+        if !self.flags.contains(FreecamFlags::HOOKED_GAME_WINDOW) { return; }
+        let platform = unsafe { crate::globals::get_platform_global().unwrap() };
+        let hwnd = platform.get_hwnd();
+        // only put the original back if nothing else has subclassed the window since
+        if !hwnd.is_invalid() && unsafe { GetWindowLongPtrW(hwnd, GWLP_WNDPROC) } == game_wnd_proc as usize as isize {
+            let prev = GAME_WND_PROC.load(Ordering::Relaxed);
+            unsafe { SetWindowLongPtrW(hwnd, GWLP_WNDPROC, prev) };
+            logln!(Verbose, "Restored game window procedure: 0x{:x}", prev as usize);
+        } else {
+            logln!(Verbose, "Game window procedure was replaced, leaving it hooked");
+        }
+        self.flags &= !FreecamFlags::HOOKED_GAME_WINDOW;
+    }
+
+    fn update_mouse_speed(&mut self) {
+        let wheel = MOUSE_WHEEL_DELTA.swap(0, Ordering::Relaxed);
+        if wheel != 0 {
+            let notches = wheel as f32 / WHEEL_DELTA as f32;
+            self.mouse_keyboard.speed = (self.mouse_keyboard.speed * KBM_SPEED_STEP.powf(notches))
+                .clamp(KBM_SPEED_MIN, KBM_SPEED_MAX);
+            logln!(Verbose, "New keyboard move speed: {:.02}x", self.mouse_keyboard.speed);
+        }
+    }
+
+    fn get_mouse_look(&mut self) -> Vec2 {
+        if !Self::check_key_held(VK_RBUTTON) {
+            self.mouse_keyboard.last_cursor = None;
+            return Vec2::ZERO;
+        }
+        let mut point: MaybeUninit<POINT> = MaybeUninit::uninit();
+        if unsafe { GetCursorPos(point.as_mut_ptr()) }.is_err() { return Vec2::ZERO; }
+        let point = unsafe { point.assume_init() };
+        let delta = match self.mouse_keyboard.last_cursor {
+            Some(last) => Vec2::new((point.x - last.x) as f32, (point.y - last.y) as f32),
+            None => Vec2::ZERO
+        };
+        self.mouse_keyboard.last_cursor = Some(point);
+        delta * KBM_LOOK_SENSITIVITY
+    }
+
+    pub(crate) fn get_mouse_keyboard_input(&mut self) -> MouseKeyboardInput {
+        if !self.flags.contains(FreecamFlags::HOOKED_GAME_WINDOW) {
+            self.hook_game_window();
+        }
+        self.update_mouse_speed();
+        let (pan_speed, move_speed) = if Self::check_key_held(VK_SHIFT) { (2., 5.) }
+        else if Self::check_key_held(VK_CONTROL) { (0.5, 0.2) }
+        else { (1., 1.) };
+        let axis = |pos: VIRTUAL_KEY, neg: VIRTUAL_KEY| {
+            (Self::check_key_held(pos) as i32 - Self::check_key_held(neg) as i32) as f32
+        };
+        let movement = Vec3::new(axis(VK_D, VK_A), axis(VK_E, VK_Q), axis(VK_W, VK_S))
+            * move_speed * self.mouse_keyboard.speed;
+        MouseKeyboardInput {
+            look: self.get_mouse_look() * pan_speed,
+            movement
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use std::f32::consts::PI;
+use std::fmt::{Display, Formatter};
 use std::num::NonZeroUsize;
-use glam::{EulerRot, Quat, Vec3A};
+use std::str::FromStr;
+use glam::{EulerRot, Mat4, Quat, Vec3A, Vec4Swizzles};
 use imgui::Ui;
 use riri_inspector_components::table::TableDraw;
 use rkyv::{Archive, Deserialize, Place, Portable, Serialize};
@@ -11,31 +14,125 @@ use rkyv::rend::{f32_be, u32_be};
 use rkyv::rend::{f32_le, u32_le};
 use rkyv::traits::NoUndef;
 use rkyv::rancor::Source as RkyvErrorSource;
+use crate::gui::utils::AppError;
 use crate::state::camera::Freecam;
 
 // quaternion
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FreecamNode {
     pub(crate) trans: Vec3A,
     pub(crate) rot: Quat,
+    // FOV to play back at this node, otherwise the FOV is left alone
+    pub(crate) fovy: Option<f32>,
+    // seconds from the start of the path. nodes are evenly spaced unless every node has one
+    pub(crate) time: Option<f32>,
 }
 
 impl FreecamNode {
     pub fn new(trans: Vec3A, rot: Quat) -> Self {
-        Self { trans, rot }
+        Self { trans, rot, fovy: None, time: None }
     }
     pub fn new_euler(trans: Vec3A, pan: f32, pitch: f32, roll: f32) -> Self {
-        let rot = Quat::from_euler(EulerRot::YXZEx, pan, pitch, roll);
-        Self { trans, rot }
+        Self::new(trans, Quat::from_euler(EulerRot::YXZEx, pan, pitch, roll))
+    }
+    // camera position and pan/pitch/roll from a scene camera's view matrix
+    pub fn get_view_euler(view: Mat4) -> (Vec3A, f32, f32, f32) {
+        let inv = view.inverse();
+        let (pan, pitch, roll) = inv.to_euler(EulerRot::YXZEx);
+        let pan = match pan >= 0. {
+            true => -(PI - pan),
+            false => pan + PI
+        };
+        (inv.w_axis.xyz().into(), pan, pitch, roll)
+    }
+    pub fn from_view_transform(view: Mat4) -> Self {
+        let (trans, pan, pitch, roll) = Self::get_view_euler(view);
+        Self::new_euler(trans, pan, pitch, roll)
+    }
+    pub fn with_fovy(mut self, fovy: f32) -> Self {
+        self.fovy = Some(fovy);
+        self
+    }
+    pub fn with_time(mut self, time: f32) -> Self {
+        self.time = Some(time);
+        self
+    }
+    // world space rotation of the camera (looking down -Z), used when converting to other software
+    pub fn get_camera_rotation(&self) -> Quat {
+        let (pan, pitch, roll) = self.rot.to_euler(EulerRot::YXZEx);
+        Quat::from_euler(EulerRot::YXZ, pan + PI, -pitch, roll)
+    }
+    pub fn from_camera_transform(trans: Vec3A, rot: Quat) -> Self {
+        let (yaw, pitch, roll) = rot.to_euler(EulerRot::YXZ);
+        Self::new_euler(trans, yaw - PI, -pitch, roll)
+    }
+}
+
+// plain text form used for the clipboard: "x y z qx qy qz qw", followed by "fovy time" when either is set
+pub(crate) const FREECAM_NODE_TEXT_HEADER: &'static str = "# P5R Freecam Nodes";
+
+impl Display for FreecamNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {} {} {} {} {}",
+            self.trans.x, self.trans.y, self.trans.z,
+            self.rot.x, self.rot.y, self.rot.z, self.rot.w)?;
+        if self.fovy.is_some() || self.time.is_some() {
+            write!(f, " {} {}", self.fovy.unwrap_or(f32::NAN), self.time.unwrap_or(f32::NAN))?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for FreecamNode {
+    type Err = AppError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values = s.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| AppError::new_owned(format!("Invalid node \"{}\": {}", s, e)))?;
+        let optional = |i: usize| values.get(i).copied().filter(|v| !v.is_nan());
+        // only fovy and time use NaN for unset, anything else would end up in the camera
+        if values.iter().take(7).any(|v| !v.is_finite()) {
+            return Err(AppError::new_owned(format!("Node position and rotation must be finite: \"{}\"", s)));
+        }
+        if values.len() >= 7 && Quat::from_xyzw(values[3], values[4], values[5], values[6]).length_squared() == 0. {
+            return Err(AppError::new_owned(format!("Node rotation can't be zero: \"{}\"", s)));
+        }
+        match values.len() {
+            7 | 8 | 9 => Ok(Self {
+                trans: Vec3A::new(values[0], values[1], values[2]),
+                rot: Quat::from_xyzw(values[3], values[4], values[5], values[6]).normalize(),
+                fovy: optional(7),
+                time: optional(8)
+            }),
+            k => Err(AppError::new_owned(format!("Expected 7 to 9 values for node, got {}", k)))
+        }
+    }
+}
+
+impl FreecamNode {
+    pub fn nodes_to_text<'a>(nodes: impl Iterator<Item = &'a FreecamNode>) -> String {
+        let mut out = FREECAM_NODE_TEXT_HEADER.to_owned();
+        for node in nodes {
+            out.push('\n');
+            out.push_str(&node.to_string());
+        }
+        out
+    }
+
+    pub fn nodes_from_text(text: &str) -> Result<Vec<FreecamNode>, AppError> {
+        text.lines()
+            .map(|l| l.trim())
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(|l| l.parse::<FreecamNode>())
+            .collect()
     }
 }
 
 impl Default for FreecamNode {
     fn default() -> Self {
-        Self {
-            trans: Vec3A::default(),
-            rot: Quat::default()
-        }
+        Self::new(Vec3A::default(), Quat::default())
     }
 }
 
@@ -52,9 +149,15 @@ pub(crate) type u32_ne = u32_be;
 #[derive(Portable)]
 pub struct ArchivedFreecamNode {
     trans: [f32_ne; 3],
-    rot: [f32_ne; 4]
+    rot: [f32_ne; 4],
+    // NaN when unset
+    fovy: f32_ne,
+    time: f32_ne
 }
 
+// .p5path files written before nodes carried FOV and time
+pub(crate) const FREECAM_NODE_LEGACY_SIZE: usize = size_of::<[f32; 7]>();
+
 unsafe impl NoUndef for ArchivedFreecamNode {}
 
 impl Into<ArchivedFreecamNode> for &FreecamNode {
@@ -62,6 +165,8 @@ impl Into<ArchivedFreecamNode> for &FreecamNode {
         ArchivedFreecamNode {
             trans: [ f32_ne::from_native(self.trans.x), f32_ne::from_native(self.trans.y), f32_ne::from_native(self.trans.z) ],
             rot: [ f32_ne::from_native(self.rot.x), f32_ne::from_native(self.rot.y), f32_ne::from_native(self.rot.z), f32_ne::from_native(self.rot.w) ],
+            fovy: f32_ne::from_native(self.fovy.unwrap_or(f32::NAN)),
+            time: f32_ne::from_native(self.time.unwrap_or(f32::NAN)),
         }
     }
 }
@@ -70,7 +175,9 @@ impl Into<FreecamNode> for &ArchivedFreecamNode {
     fn into(self) -> FreecamNode {
         FreecamNode {
             trans: Vec3A::new(f32_ne::to_native(self.trans[0]), f32_ne::to_native(self.trans[1]), f32_ne::to_native(self.trans[2])),
-            rot: Quat::from_array([ f32_ne::to_native(self.rot[0]), f32_ne::to_native(self.rot[1]), f32_ne::to_native(self.rot[2]), f32_ne::to_native(self.rot[3]) ])
+            rot: Quat::from_array([ f32_ne::to_native(self.rot[0]), f32_ne::to_native(self.rot[1]), f32_ne::to_native(self.rot[2]), f32_ne::to_native(self.rot[3]) ]),
+            fovy: Some(f32_ne::to_native(self.fovy)).filter(|v| !v.is_nan()),
+            time: Some(f32_ne::to_native(self.time)).filter(|v| !v.is_nan()),
         }
     }
 }
@@ -120,7 +227,13 @@ pub struct FreecamNodeEntry<'a> {
 impl<'a> TableDraw<Freecam> for FreecamNodeEntry<'a> {
     fn draw_contents(&self, ui: &Ui, ctx: &mut Freecam, index: usize) {
         match index {
-            0 => ui.text(format!("{}", self.index)),
+            0 => {
+                let selected = ctx.node_selection.contains(&self.index);
+                if ui.selectable_config(format!("{}##SelectForFreecamNodeEntry", self.index))
+                    .selected(selected).build() {
+                    ctx.select_node(self.index, ui.io().key_ctrl, ui.io().key_shift);
+                }
+            },
             1 => {
                 let mut trans: [f32; 3] = self.node.trans.into();
                 ui.set_next_item_width(ui.content_region_avail()[0]);
@@ -137,12 +250,13 @@ impl<'a> TableDraw<Freecam> for FreecamNodeEntry<'a> {
             },
             3 => {
                 if ui.button(format!("Go To##ForFreecamNodeEntry{}", self.index)) {
-                    ctx.node_path_current = (self.index as f32 / (ctx.nodes.len() - 1) as f32) * ctx.node_path_time;
+                    ctx.node_path_current = ctx.get_node_path_time(self.index);
                     ctx.camera_path_tick(0.);
                 }
                 ui.same_line_with_spacing(0., 10.);
                 if ui.button(format!("Remove##ForFreecamNodeEntry{}", self.index)) {
                     ctx.nodes.remove(self.index);
+                    ctx.clear_node_selection();
                 }
             },
             _ => ()
@@ -154,4 +268,77 @@ impl<'a> FreecamNodeEntry<'a> {
     pub fn new(node: &'a FreecamNode, index: usize) -> Self {
         Self { node, index }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_node() -> FreecamNode {
+        FreecamNode::new_euler(Vec3A::new(12.5, -340., 1024.25), 0.7, -0.3, 0.15)
+    }
+
+    // parsing renormalizes the rotation, which can move it by an ulp
+    fn assert_node_eq(a: &FreecamNode, b: &FreecamNode) {
+        assert_eq!(a.trans, b.trans);
+        assert!(a.rot.abs_diff_eq(b.rot, 1e-6), "{:?} != {:?}", a.rot, b.rot);
+        assert_eq!((a.fovy, a.time), (b.fovy, b.time));
+    }
+
+    fn round_trip(node: &FreecamNode) -> FreecamNode {
+        node.to_string().parse::<FreecamNode>().unwrap()
+    }
+
+    #[test]
+    fn text_round_trip_without_fovy_or_time() {
+        let node = test_node();
+        assert_eq!(node.to_string().split_whitespace().count(), 7);
+        assert_node_eq(&round_trip(&node), &node);
+    }
+
+    #[test]
+    fn text_round_trip_with_fovy() {
+        let node = test_node().with_fovy(38.5);
+        let parsed = round_trip(&node);
+        assert_node_eq(&parsed, &node);
+        assert_eq!(parsed.time, None);
+        // the 8 value form only has a fovy
+        let short = format!("{} {} {} {} {} {} {} 38.5", node.trans.x, node.trans.y, node.trans.z,
+            node.rot.x, node.rot.y, node.rot.z, node.rot.w);
+        assert_node_eq(&short.parse::<FreecamNode>().unwrap(), &node);
+    }
+
+    #[test]
+    fn text_round_trip_with_fovy_and_time() {
+        let node = test_node().with_fovy(60.).with_time(2.25);
+        assert_eq!(node.to_string().split_whitespace().count(), 9);
+        assert_node_eq(&round_trip(&node), &node);
+        let timed = test_node().with_time(0.5);
+        assert_node_eq(&round_trip(&timed), &timed);
+    }
+
+    #[test]
+    fn text_round_trip_with_header() {
+        let nodes = vec![test_node(), test_node().with_fovy(45.), test_node().with_fovy(50.).with_time(1.)];
+        let text = FreecamNode::nodes_to_text(nodes.iter());
+        assert_eq!(text.lines().next(), Some(FREECAM_NODE_TEXT_HEADER));
+        let parsed = FreecamNode::nodes_from_text(&text).unwrap();
+        assert_eq!(parsed.len(), nodes.len());
+        parsed.iter().zip(&nodes).for_each(|(a, b)| assert_node_eq(a, b));
+        assert!(FreecamNode::nodes_from_text(FREECAM_NODE_TEXT_HEADER).unwrap().is_empty());
+    }
+
+    #[test]
+    fn text_rejects_bad_nodes() {
+        assert!("1 2 3 0 0 0".parse::<FreecamNode>().is_err());
+        assert!("1 2 3 0 0 0 1 45 1 2".parse::<FreecamNode>().is_err());
+        assert!("1 2 three 0 0 0 1".parse::<FreecamNode>().is_err());
+        assert!("NaN 2 3 0 0 0 1".parse::<FreecamNode>().is_err());
+        assert!("1 2 3 0 0 NaN 1".parse::<FreecamNode>().is_err());
+        assert!("1 inf 3 0 0 0 1".parse::<FreecamNode>().is_err());
+        assert!("1 2 3 0 0 0 0".parse::<FreecamNode>().is_err());
+        // NaN still means unset for fovy and time
+        let node = "1 2 3 0 0 0 1 NaN NaN".parse::<FreecamNode>().unwrap();
+        assert_eq!((node.fovy, node.time), (None, None));
+    }
+}
@@ -0,0 +1,165 @@
+use glam::{Vec3, Vec3A};
+use imgui::{TreeNodeFlags, Ui};
+use riri_mod_tools_rt::logln;
+use windows::Win32::UI::Input::KeyboardAndMouse::{VK_O, VK_P};
+use crate::state::camera::{Freecam, FREECAM_MOVE_SPEED};
+
+pub(crate) const ORBIT_DEFAULT_DISTANCE: f32 = 300.;
+pub(crate) const ORBIT_MIN_DISTANCE: f32 = 10.;
+// zoom factor is e^(ORBIT_ZOOM_SPEED) per second at full stick deflection
+pub(crate) const ORBIT_ZOOM_SPEED: f32 = 1.5;
+// stop just short of straight up/down so the orbit doesn't flip over the pivot
+pub(crate) const ORBIT_PITCH_LIMIT: f32 = 1.55;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CameraMode {
+    Fly,
+    Orbit,
+    Follow,
+    Dolly
+}
+
+impl CameraMode {
+    pub const ALL: [Self; 4] = [ Self::Fly, Self::Orbit, Self::Follow, Self::Dolly ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Fly => "Fly",
+            Self::Orbit => "Orbit",
+            Self::Follow => "Follow",
+            Self::Dolly => "Dolly Zoom"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitPivot {
+    Point,
+    FieldTarget
+}
+
+impl OrbitPivot {
+    pub const ALL: [Self; 2] = [ Self::Point, Self::FieldTarget ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Point => "World Point",
+            Self::FieldTarget => "Field Camera Target"
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct OrbitState {
+    pub(crate) pivot_type: OrbitPivot,
+    pub(crate) pivot: Vec3A,
+    pub(crate) distance: f32,
+}
+
+impl Default for OrbitState {
+    fn default() -> Self {
+        Self { pivot_type: OrbitPivot::Point, pivot: Vec3A::ZERO, distance: ORBIT_DEFAULT_DISTANCE }
+    }
+}
+
+impl Freecam {
+    fn get_orbit_target() -> Option<Vec3A> {
+        Self::get_field_camera().map(|c| c.get_target_pos())
+    }
+
+    // look at the pivot from where the camera currently is
+    fn face_orbit_pivot(&mut self) {
+        let back = self.camera_pos - self.orbit.pivot;
+        self.orbit.distance = back.length().max(ORBIT_MIN_DISTANCE);
+        let back = back.normalize_or_zero();
+        if back == Vec3A::ZERO { return; }
+        self.pitch = back.y.asin().clamp(-ORBIT_PITCH_LIMIT, ORBIT_PITCH_LIMIT);
+        self.pan = (-back.x).atan2(-back.z);
+    }
+
+    pub(crate) fn set_camera_mode(&mut self, mode: CameraMode) {
+        if mode == CameraMode::Follow && !self.attach_follow_target() { return; }
+        if mode == CameraMode::Dolly && !self.begin_dolly_zoom() { return; }
+        if mode == CameraMode::Orbit {
+            match self.orbit.pivot_type {
+                OrbitPivot::FieldTarget => match Self::get_orbit_target() {
+                    Some(v) => {
+                        self.orbit.pivot = v;
+                        self.face_orbit_pivot();
+                    },
+                    None => {
+                        logln!(Verbose, "No field camera target, orbiting a point in front of the camera instead");
+                        self.orbit.pivot_type = OrbitPivot::Point;
+                        self.orbit.pivot = self.camera_pos - Self::get_back_vector(self.pan, self.pitch) * self.orbit.distance;
+                    }
+                },
+                OrbitPivot::Point => self.orbit.pivot = self.camera_pos - Self::get_back_vector(self.pan, self.pitch) * self.orbit.distance
+            }
+        }
+        if self.camera_mode != mode { logln!(Verbose, "Camera mode: {}", mode.get_name()); }
+        self.camera_mode = mode;
+        self.reset_smoothing();
+    }
+
+    pub fn update_camera_mode(&mut self) {
+        if Self::check_key_pressed(VK_O) {
+            match self.camera_mode {
+                CameraMode::Orbit => self.set_camera_mode(CameraMode::Fly),
+                _ => self.set_camera_mode(CameraMode::Orbit),
+            }
+        } else if Self::check_key_pressed(VK_P) {
+            match self.camera_mode {
+                CameraMode::Follow => self.set_camera_mode(CameraMode::Fly),
+                _ => self.set_camera_mode(CameraMode::Follow),
+            }
+        }
+    }
+
+    // rot_delta rotates around the pivot, back movement zooms and the rest slides a world point pivot
+    pub(crate) fn apply_orbit_motion(&mut self, rot_delta: Vec3, move_delta: Vec3) {
+        if self.orbit.pivot_type == OrbitPivot::FieldTarget {
+            if let Some(v) = Self::get_orbit_target() { self.orbit.pivot = v; }
+        }
+        self.pan += rot_delta.x;
+        self.pitch = (self.pitch + rot_delta.y).clamp(-ORBIT_PITCH_LIMIT, ORBIT_PITCH_LIMIT);
+        self.roll += rot_delta.z;
+        self.orbit.distance = (self.orbit.distance * (move_delta.z / FREECAM_MOVE_SPEED * ORBIT_ZOOM_SPEED).exp())
+            .max(ORBIT_MIN_DISTANCE);
+        if self.orbit.pivot_type == OrbitPivot::Point {
+            self.orbit.pivot += Self::get_move_vector(self.pan, self.pitch, Vec3::new(move_delta.x, move_delta.y, 0.));
+        }
+        self.camera_pos = self.orbit.pivot + Self::get_back_vector(self.pan, self.pitch) * self.orbit.distance;
+    }
+
+    pub(crate) fn draw_contents_orbit(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Camera Mode##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        let mut mode = CameraMode::ALL.iter().position(|m| *m == self.camera_mode).unwrap();
+        ui.set_next_item_width(max_width / 4.);
+        if ui.combo("Mode##ForFreecamOrbit", &mut mode, &CameraMode::ALL, |m| m.get_name().into()) {
+            self.set_camera_mode(CameraMode::ALL[mode]);
+        }
+        ui.same_line_with_spacing(0., 10.);
+        let mut pivot = OrbitPivot::ALL.iter().position(|p| *p == self.orbit.pivot_type).unwrap();
+        ui.set_next_item_width(max_width / 4.);
+        if ui.combo("Pivot##ForFreecamOrbit", &mut pivot, &OrbitPivot::ALL, |p| p.get_name().into()) {
+            self.orbit.pivot_type = OrbitPivot::ALL[pivot];
+            if self.camera_mode == CameraMode::Orbit { self.set_camera_mode(CameraMode::Orbit); }
+        }
+        ui.disabled(self.orbit.pivot_type != OrbitPivot::Point, || {
+            let mut pivot: [f32; 3] = self.orbit.pivot.into();
+            ui.set_next_item_width(max_width / 2.);
+            if ui.input_float3("Pivot Point##ForFreecamOrbit", &mut pivot).display_format("%.1f").build() {
+                self.orbit.pivot = pivot.into();
+                if self.camera_mode == CameraMode::Orbit { self.face_orbit_pivot(); }
+            }
+        });
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(max_width / 5.);
+        if ui.input_float("Distance##ForFreecamOrbit", &mut self.orbit.distance).display_format("%.1f").build() {
+            self.orbit.distance = self.orbit.distance.max(ORBIT_MIN_DISTANCE);
+        }
+        self.draw_contents_follow(ui);
+        self.draw_contents_dolly(ui);
+    }
+}
@@ -0,0 +1,93 @@
+use std::f32::consts::PI;
+use glam::{EulerRot, Mat4, Quat, Vec3, Vec3A};
+use imgui::Ui;
+use crate::state::camera::Freecam;
+
+// Quaternion orientation for manual flight. The local frame faces +Z with +Y up and -X to the
+// right, matching the pan/pitch/roll model so switching between the two doesn't jump.
+#[derive(Debug)]
+pub struct OrientationState {
+    pub(crate) enabled: bool,
+    rot: Quat,
+    // pan/pitch/roll last written from rot, used to notice when something else moved the camera
+    euler: Vec3,
+    target_rot: Quat,
+    // set when the current view was built from the quaternion, roll is already part of it
+    view_active: bool,
+}
+
+impl Default for OrientationState {
+    fn default() -> Self {
+        Self { enabled: false, rot: Quat::IDENTITY, euler: Vec3::ZERO, target_rot: Quat::IDENTITY, view_active: false }
+    }
+}
+
+impl Freecam {
+    fn sync_orientation(&mut self) {
+        let euler = Vec3::new(self.pan, self.pitch, self.roll);
+        if euler != self.orientation.euler {
+            self.orientation.rot = Quat::from_euler(EulerRot::YXZ, self.pan, self.pitch, self.roll);
+            self.orientation.target_rot = self.orientation.rot;
+            self.orientation.euler = euler;
+        }
+    }
+
+    fn store_orientation(&mut self) {
+        (self.pan, self.pitch, self.roll) = self.orientation.rot.to_euler(EulerRot::YXZ);
+        self.orientation.euler = Vec3::new(self.pan, self.pitch, self.roll);
+    }
+
+    // rot_delta is (yaw, pitch, roll) around the camera's own axes, move_delta is (right, up, back)
+    pub(crate) fn apply_quaternion_motion(&mut self, rot_delta: Vec3, move_delta: Vec3, delta: f32) {
+        self.sync_orientation();
+        let local = Quat::from_rotation_y(rot_delta.x) * Quat::from_rotation_x(rot_delta.y) * Quat::from_rotation_z(rot_delta.z);
+        let movement = Vec3A::new(-move_delta.x, move_delta.y, -move_delta.z);
+        if self.smoothing.settings.enabled {
+            self.orientation.target_rot = (self.orientation.target_rot * local).normalize();
+            // exponential approach, reaching ~98% of the way after rotation_time
+            let t = 1. - (-4. * delta / self.smoothing.settings.rotation_time.max(f32::EPSILON)).exp();
+            self.orientation.rot = self.orientation.rot.slerp(self.orientation.target_rot, t).normalize();
+            let target = self.orientation.target_rot;
+            self.apply_smoothed_translation(target * movement, delta);
+        } else {
+            self.orientation.rot = (self.orientation.rot * local).normalize();
+            self.orientation.target_rot = self.orientation.rot;
+            self.camera_pos += self.orientation.rot * movement;
+        }
+        self.store_orientation();
+        self.orientation.view_active = true;
+    }
+
+    pub(crate) fn reset_orientation_view(&mut self) {
+        self.orientation.view_active = false;
+    }
+
+    pub(crate) fn get_quaternion_view_matrix(&mut self) -> Mat4 {
+        let rot = self.orientation.rot;
+        self.lookat_pos = self.camera_pos + rot * Vec3A::Z * 100.;
+        self.up_vec = rot * Vec3A::Y;
+        // view space looks down -Z with +X to the right
+        Mat4::from_rotation_translation(rot * Quat::from_rotation_y(PI), self.camera_pos.into()).inverse()
+    }
+
+    pub(crate) fn is_quaternion_view_active(&self) -> bool {
+        self.orientation.view_active
+    }
+
+    // roll to hand to the game camera alongside the view matrix
+    pub fn get_view_roll(&self) -> f32 {
+        match self.orientation.view_active {
+            true => 0.,
+            false => self.roll
+        }
+    }
+
+    pub(crate) fn draw_contents_orientation(&mut self, ui: &Ui) {
+        if ui.checkbox("Quaternion##ForFreecamWindow", &mut self.orientation.enabled) {
+            self.reset_smoothing();
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Fly mode turns around the camera's own axes, so rolled and overhead shots don't flip");
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use glam::{Vec2, Vec3};
+use imgui::{TreeNodeFlags, Ui};
+use opengfd::io::controller::ControllerButton;
+use rkyv::{Archive, Deserialize, Serialize};
+use crate::state::camera::{Freecam, PAD_STICK_RANGE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+pub enum PadButton {
+    None,
+    LeftTrigger,
+    RightTrigger,
+    LeftShoulder,
+    RightShoulder,
+    DpadUp,
+    DpadDown,
+    DpadLeft,
+    DpadRight
+}
+
+impl PadButton {
+    pub const ALL: [Self; 9] = [
+        Self::None, Self::LeftTrigger, Self::RightTrigger, Self::LeftShoulder, Self::RightShoulder,
+        Self::DpadUp, Self::DpadDown, Self::DpadLeft, Self::DpadRight
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::LeftTrigger => "LT",
+            Self::RightTrigger => "RT",
+            Self::LeftShoulder => "LB",
+            Self::RightShoulder => "RB",
+            Self::DpadUp => "D-Pad Up",
+            Self::DpadDown => "D-Pad Down",
+            Self::DpadLeft => "D-Pad Left",
+            Self::DpadRight => "D-Pad Right"
+        }
+    }
+
+    fn get_flag(&self) -> ControllerButton {
+        match self {
+            Self::None => ControllerButton::empty(),
+            Self::LeftTrigger => ControllerButton::LEFT_TRIGGER,
+            Self::RightTrigger => ControllerButton::RIGHT_TRIGGER,
+            Self::LeftShoulder => ControllerButton::LEFT_SHOULDER,
+            Self::RightShoulder => ControllerButton::RIGHT_SHOULDER,
+            Self::DpadUp => ControllerButton::DPAD_UP,
+            Self::DpadDown => ControllerButton::DPAD_DOWN,
+            Self::DpadLeft => ControllerButton::DPAD_LEFT,
+            Self::DpadRight => ControllerButton::DPAD_RIGHT
+        }
+    }
+
+    fn is_held(&self, buttons: ControllerButton) -> bool {
+        *self != Self::None && buttons.contains(self.get_flag())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadAction {
+    SpeedUp,
+    SlowDown,
+    MoveUp,
+    MoveDown,
+    RollIncrease,
+    RollDecrease,
+    NextPreset
+}
+
+impl PadAction {
+    pub const ALL: [Self; 7] = [
+        Self::SpeedUp, Self::SlowDown, Self::MoveUp, Self::MoveDown, Self::RollIncrease, Self::RollDecrease,
+        Self::NextPreset
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::SpeedUp => "Speed Up",
+            Self::SlowDown => "Slow Down",
+            Self::MoveUp => "Move Up",
+            Self::MoveDown => "Move Down",
+            Self::RollIncrease => "Roll +",
+            Self::RollDecrease => "Roll -",
+            Self::NextPreset => "Next Speed Preset"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    Linear,
+    Quadratic,
+    Cubic
+}
+
+impl ResponseCurve {
+    pub const ALL: [Self; 3] = [ Self::Linear, Self::Quadratic, Self::Cubic ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Self::Linear => "Linear",
+            Self::Quadratic => "Quadratic",
+            Self::Cubic => "Cubic"
+        }
+    }
+
+    // input and output are in 0..1
+    fn apply(&self, v: f32) -> f32 {
+        match self {
+            Self::Linear => v,
+            Self::Quadratic => v * v,
+            Self::Cubic => v * v * v
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct PadSettings {
+    // indexed by PadAction
+    pub(crate) bindings: [PadButton; PadAction::ALL.len()],
+    pub(crate) look_sensitivity: f32,
+    pub(crate) move_sensitivity: f32,
+    pub(crate) roll_sensitivity: f32,
+    // (look, move) multipliers while the speed up/slow down buttons are held
+    pub(crate) fast_multiplier: [f32; 2],
+    pub(crate) slow_multiplier: [f32; 2],
+    pub(crate) look_curve: ResponseCurve,
+    pub(crate) move_curve: ResponseCurve,
+    // fraction of the stick range that's ignored
+    pub(crate) look_dead_zone: f32,
+    pub(crate) move_dead_zone: f32,
+    pub(crate) invert_look_y: bool,
+}
+
+impl Default for PadSettings {
+    fn default() -> Self {
+        Self {
+            bindings: [
+                PadButton::LeftTrigger, PadButton::RightTrigger,
+                PadButton::LeftShoulder, PadButton::RightShoulder,
+                PadButton::DpadUp, PadButton::DpadDown,
+                PadButton::DpadRight
+            ],
+            look_sensitivity: 1.,
+            move_sensitivity: 1.,
+            roll_sensitivity: 1.,
+            fast_multiplier: [2., 5.],
+            slow_multiplier: [0.5, 0.2],
+            look_curve: ResponseCurve::Linear,
+            move_curve: ResponseCurve::Linear,
+            look_dead_zone: 0.1,
+            move_dead_zone: 0.1,
+            invert_look_y: false,
+        }
+    }
+}
+
+// look and movement are in stick deflections with speed modifiers and sensitivity applied,
+// movement is (right, up, back)
+#[derive(Debug, Default)]
+pub struct PadInput {
+    pub(crate) look: Vec2,
+    pub(crate) movement: Vec3,
+    pub(crate) roll: f32,
+}
+
+// radial dead zone, rescaled so output starts from zero at the edge of the dead zone
+fn apply_stick_response(stick: Vec2, dead_zone: f32, curve: ResponseCurve) -> Vec2 {
+    let len = stick.length();
+    if len <= dead_zone || dead_zone >= 1. { return Vec2::ZERO; }
+    let scaled = ((len - dead_zone) / (1. - dead_zone)).min(1.);
+    stick / len * curve.apply(scaled)
+}
+
+impl Freecam {
+    pub(crate) fn is_action_held(&self, action: PadAction, buttons: ControllerButton) -> bool {
+        self.pad.bindings[action as usize].is_held(buttons)
+    }
+
+    pub(crate) fn get_pad_input(&self) -> PadInput {
+        let ctrl = unsafe { crate::globals::get_pad_instance().unwrap() };
+        let lstick = ctrl.get_current().get_lstick();
+        let rstick = ctrl.get_current().get_rstick();
+        let buttons = ctrl.get_current().get_hold_press();
+        let settings = &self.pad;
+
+        let [look_speed, move_speed] = if self.is_action_held(PadAction::SpeedUp, buttons) { settings.fast_multiplier }
+        else if self.is_action_held(PadAction::SlowDown, buttons) { settings.slow_multiplier }
+        else { [1., 1.] };
+
+        let look = Vec2::new(rstick.get_horizontal() as f32, rstick.get_vertical() as f32) / PAD_STICK_RANGE;
+        let mut look = apply_stick_response(look, settings.look_dead_zone, settings.look_curve);
+        if settings.invert_look_y { look.y = -look.y; }
+        let stick = Vec2::new(lstick.get_horizontal() as f32, lstick.get_vertical() as f32) / PAD_STICK_RANGE;
+        let stick = apply_stick_response(stick, settings.move_dead_zone, settings.move_curve);
+        let lift = (self.is_action_held(PadAction::MoveUp, buttons) as i32
+            - self.is_action_held(PadAction::MoveDown, buttons) as i32) as f32;
+        let roll = (self.is_action_held(PadAction::RollIncrease, buttons) as i32
+            - self.is_action_held(PadAction::RollDecrease, buttons) as i32) as f32;
+        PadInput {
+            look: look * settings.look_sensitivity * look_speed,
+            movement: Vec3::new(stick.x, lift, stick.y) * settings.move_sensitivity * move_speed,
+            roll: roll * settings.roll_sensitivity
+        }
+    }
+
+    pub(crate) fn draw_contents_pad(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Controller##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        for (i, action) in PadAction::ALL.iter().enumerate() {
+            if i % 2 != 0 { ui.same_line_with_spacing(0., 10.); }
+            let mut button = PadButton::ALL.iter().position(|b| *b == self.pad.bindings[i]).unwrap();
+            ui.set_next_item_width(max_width / 4.);
+            if ui.combo(format!("{}##ForFreecamPad", action.get_name()), &mut button, &PadButton::ALL, |b| b.get_name().into()) {
+                self.pad.bindings[i] = PadButton::ALL[button];
+            }
+        }
+        let width = max_width / 5.;
+        ui.set_next_item_width(width);
+        ui.slider_config("Look Sensitivity##ForFreecamPad", 0.1, 4.).display_format("%.2f").build(&mut self.pad.look_sensitivity);
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        ui.slider_config("Move Sensitivity##ForFreecamPad", 0.1, 4.).display_format("%.2f").build(&mut self.pad.move_sensitivity);
+        ui.set_next_item_width(width);
+        ui.slider_config("Roll Sensitivity##ForFreecamPad", 0.1, 4.).display_format("%.2f").build(&mut self.pad.roll_sensitivity);
+        ui.same_line_with_spacing(0., 10.);
+        ui.checkbox("Invert Look Y##ForFreecamPad", &mut self.pad.invert_look_y);
+        ui.set_next_item_width(width);
+        ui.input_float2("Fast (Look, Move)##ForFreecamPad", &mut self.pad.fast_multiplier).display_format("%.2f").build();
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        ui.input_float2("Slow (Look, Move)##ForFreecamPad", &mut self.pad.slow_multiplier).display_format("%.2f").build();
+        let mut look_curve = ResponseCurve::ALL.iter().position(|c| *c == self.pad.look_curve).unwrap();
+        ui.set_next_item_width(width);
+        if ui.combo("Look Curve##ForFreecamPad", &mut look_curve, &ResponseCurve::ALL, |c| c.get_name().into()) {
+            self.pad.look_curve = ResponseCurve::ALL[look_curve];
+        }
+        ui.same_line_with_spacing(0., 10.);
+        let mut move_curve = ResponseCurve::ALL.iter().position(|c| *c == self.pad.move_curve).unwrap();
+        ui.set_next_item_width(width);
+        if ui.combo("Move Curve##ForFreecamPad", &mut move_curve, &ResponseCurve::ALL, |c| c.get_name().into()) {
+            self.pad.move_curve = ResponseCurve::ALL[move_curve];
+        }
+        ui.set_next_item_width(width);
+        ui.slider_config("Look Dead Zone##ForFreecamPad", 0., 0.9).display_format("%.2f").build(&mut self.pad.look_dead_zone);
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        ui.slider_config("Move Dead Zone##ForFreecamPad", 0., 0.9).display_format("%.2f").build(&mut self.pad.move_dead_zone);
+        if ui.button("Reset##ForFreecamPad") { self.pad = PadSettings::default(); }
+    }
+}
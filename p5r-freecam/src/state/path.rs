@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::ops::{Add, Mul};
 use glam::{EulerRot, Mat4, Quat, Vec3A};
-use imgui::Ui;
+use imgui::{Key, Ui};
 use implot::{Axis, Plot, PlotScatter};
 use opengfd::kernel::allocator::GfdAllocator;
 use crate::state::camera::Freecam;
@@ -46,6 +46,7 @@ impl Freecam {
         (self.pan, self.pitch, self.roll) = payload.rot.to_euler(EulerRot::YXZEx);
         // logln!(Verbose, "{}: <pan: {}, pitch: {}, roll: {}>", self.node_path_percent, self.pan, self.pitch, self.roll);
         self.camera_pos = payload.trans;
+        self.reset_smoothing();
         // set lookat and up vec
         self.lookat_pos = self.camera_pos - Vec3A::new(
             -(self.pan.sin() * self.pitch.cos()),
@@ -63,6 +64,7 @@ impl Freecam {
         (self.pan, self.pitch, self.roll) = self.last_interp.rot.to_euler(EulerRot::YXZEx);
         // logln!(Verbose, "{}: <pan: {}, pitch: {}, roll: {}>", self.node_path_percent, self.pan, self.pitch, self.roll);
         self.camera_pos = self.last_interp.trans;
+        self.reset_smoothing();
         // set lookat and up vec
         self.lookat_pos = self.camera_pos - Vec3A::new(
             -(self.pan.sin() * self.pitch.cos()),
@@ -82,6 +84,83 @@ impl Freecam {
         let new = FreecamNode::new(trans, rot);
         logln!(Verbose, "Add node #{} {:?} <pan: {}, pitch: {}, roll: {}>", self.nodes.len() + 1, new, self.pan, self.pitch, self.roll);
         self.nodes.push(new);
+        self.mark_session_dirty();
+    }
+
+    // every node has a time, so playback follows those instead of spacing nodes evenly
+    // times out of order (e.g. pasted mid-path) can't be sampled, so those paths fall back to even spacing
+    pub(crate) fn is_path_timed(&self) -> bool {
+        self.nodes.len() > 1
+            && self.nodes.iter().all(|n| n.time.is_some_and(|t| t.is_finite()))
+            && self.nodes.windows(2).all(|w| w[0].time <= w[1].time)
+    }
+
+    fn get_timed_path_range(&self) -> (f32, f32) {
+        let first = self.nodes.first().and_then(|n| n.time).unwrap_or(0.);
+        let last = self.nodes.last().and_then(|n| n.time).unwrap_or(0.);
+        (first, (last - first).max(0.))
+    }
+
+    // match the playback length to the node times
+    pub(crate) fn fit_path_time_to_nodes(&mut self) {
+        if !self.is_path_timed() { return; }
+        let (_, duration) = self.get_timed_path_range();
+        if duration > 0. { self.node_path_time = duration; }
+    }
+
+    // path time that lands on a node
+    pub(crate) fn get_node_path_time(&self, index: usize) -> f32 {
+        match (self.is_path_timed(), self.nodes.len()) {
+            (true, _) => {
+                let (first, duration) = self.get_timed_path_range();
+                let time = self.nodes[index].time.unwrap_or(first) - first;
+                if duration > 0. { time / duration * self.node_path_time } else { 0. }
+            },
+            (false, k) if k > 1 => index as f32 / (k - 1) as f32 * self.node_path_time,
+            _ => 0.
+        }
+    }
+
+    // nodes either side of the current path position and how far along between them
+    fn get_path_segment(&self) -> Option<(usize, usize, f32)> {
+        let count = self.nodes.len();
+        if count == 0 { return None; }
+        if count == 1 { return Some((0, 0, 0.)); }
+        match self.is_path_timed() {
+            true => {
+                let (first, duration) = self.get_timed_path_range();
+                let time = first + self.node_path_percent.clamp(0., 1.) * duration;
+                // nodes sharing a time are a cut, so this lands after it
+                let next = self.nodes.partition_point(|n| n.time.unwrap() <= time);
+                match (next.checked_sub(1), next < count) {
+                    (Some(a), true) => {
+                        let (ta, tb) = (self.nodes[a].time.unwrap(), self.nodes[next].time.unwrap());
+                        Some((a, next, (time - ta) / (tb - ta)))
+                    },
+                    (Some(a), false) => Some((a, a, 0.)),
+                    (None, _) => Some((0, 0, 0.))
+                }
+            },
+            false => {
+                let u = self.node_path_percent.clamp(0., 1.) * (count - 1) as f32;
+                let a = (u as usize).min(count - 2);
+                Some((a, a + 1, u - a as f32))
+            }
+        }
+    }
+
+    fn sample_timed_path(&self) -> Option<FreecamNode> {
+        let (a, b, t) = self.get_path_segment()?;
+        let (a, b) = (&self.nodes[a], &self.nodes[b]);
+        Some(FreecamNode::new(a.trans.lerp(b.trans, t), a.rot.slerp(b.rot, t)))
+    }
+
+    fn sample_path_fovy(&self) -> Option<f32> {
+        let (a, b, t) = self.get_path_segment()?;
+        match (self.nodes[a].fovy, self.nodes[b].fovy) {
+            (Some(a), Some(b)) => Some(a + (b - a) * t),
+            (a, b) => a.or(b)
+        }
     }
 
     pub fn camera_path_tick(&mut self, delta: f32) {
@@ -98,6 +177,7 @@ impl Freecam {
                 self.flags &= !FreecamFlags::PLAYING_PATH; // stop immediately
                 Some(FreecamNode::new(node.trans, node.rot))
             },
+            _ if self.is_path_timed() => self.sample_timed_path(), // piecewise linear on node times
             2 => { // lerp
                 let first = self.nodes.first().unwrap();
                 let last = self.nodes.last().unwrap();
@@ -127,6 +207,7 @@ impl Freecam {
         } {
             if let Some(cam) = Self::get_scene_camera_mut() {
                 self.set_position_from_interp(cam, payload);
+                if let Some(fovy) = self.sample_path_fovy() { cam.set_fovy(fovy); }
             }
         }
         if self.node_path_current >= self.node_path_time {
@@ -151,6 +232,7 @@ impl Freecam {
                 let old_id = self.nodes.len();
                 let rem = self.nodes.pop().unwrap();
                 logln!(Verbose, "Removed node #{} {:?}", old_id, rem);
+                self.mark_session_dirty();
             } else {
                 logln!(Verbose, "Node list is already empty");
             }
@@ -159,6 +241,7 @@ impl Freecam {
             if self.nodes.len() > 0 {
                 logln!(Verbose, "Cleared node list (had {} nodes)", self.nodes.len());
                 self.nodes.clear();
+                self.mark_session_dirty();
             } else {
                 logln!(Verbose, "Node list is already empty");
             }
@@ -219,7 +302,73 @@ impl Freecam {
         Ok(true)
     }
 
+    pub(crate) fn select_node(&mut self, index: usize, additive: bool, range: bool) {
+        match (range, self.node_selection_anchor) {
+            (true, Some(anchor)) => {
+                // the anchor stays put so repeated shift-clicks resize the same range
+                if !additive { self.node_selection.clear(); }
+                self.node_selection.extend(anchor.min(index)..=anchor.max(index));
+            },
+            _ => {
+                if additive {
+                    if !self.node_selection.remove(&index) { self.node_selection.insert(index); }
+                } else {
+                    self.node_selection.clear();
+                    self.node_selection.insert(index);
+                }
+                self.node_selection_anchor = Some(index);
+            }
+        }
+    }
+
+    pub(crate) fn clear_node_selection(&mut self) {
+        self.node_selection.clear();
+        self.node_selection_anchor = None;
+    }
+
+    pub(crate) fn copy_selected_nodes(&self, ui: &Ui) {
+        let text = match self.node_selection.is_empty() {
+            true => FreecamNode::nodes_to_text(self.nodes.iter()),
+            false => FreecamNode::nodes_to_text(self.node_selection.iter().filter_map(|i| self.nodes.get(*i)))
+        };
+        let count = text.lines().count() - 1;
+        ui.set_clipboard_text(text);
+        logln!(Verbose, "Copied {} nodes to clipboard", count);
+    }
+
+    pub(crate) fn paste_nodes(&mut self, ui: &Ui) {
+        let text = match ui.clipboard_text() {
+            Some(v) => v, None => return
+        };
+        match FreecamNode::nodes_from_text(&text) {
+            Ok(pasted) => {
+                // insert after the selection, otherwise append to the end of the path
+                let start = self.node_selection.last().map_or(self.nodes.len(), |i| (*i + 1).min(self.nodes.len()));
+                let count = pasted.len();
+                self.nodes.splice(start..start, pasted);
+                self.node_selection = (start..start + count).collect();
+                self.node_selection_anchor = Some(start);
+                self.mark_session_dirty();
+                logln!(Verbose, "Pasted {} nodes at #{}", count, start);
+            },
+            Err(e) => logln!(Verbose, "Clipboard doesn't contain freecam nodes: {}", e),
+        }
+    }
+
     pub(crate) fn draw_contents_keyframes(&mut self, ui: &Ui) {
+        // copy/paste
+        self.node_selection.retain(|i| *i < self.nodes.len());
+        if self.node_selection_anchor.is_some_and(|i| i >= self.nodes.len()) { self.node_selection_anchor = None; }
+        ui.disabled(self.nodes.is_empty(), || {
+            if ui.button("Copy Nodes##ForFreecamWindow") { self.copy_selected_nodes(ui); }
+        });
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Paste Nodes##ForFreecamWindow") { self.paste_nodes(ui); }
+        if !ui.io().want_text_input && ui.io().key_ctrl {
+            if ui.is_key_pressed(Key::C) && !self.nodes.is_empty() { self.copy_selected_nodes(ui); }
+            if ui.is_key_pressed(Key::V) { self.paste_nodes(ui); }
+            if ui.is_key_pressed(Key::A) { self.node_selection = (0..self.nodes.len()).collect(); }
+        }
         // path nodes
         let content_area = ui.content_region_avail();
         let mut table: InspectorTable<FreecamNodeEntry<'_>, Self, 4> = InspectorTable::new(
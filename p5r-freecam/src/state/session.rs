@@ -0,0 +1,301 @@
+use std::error::Error;
+use std::path::PathBuf;
+use riri_mod_tools_rt::{logln, mod_loader_data};
+use rkyv::{Archive, Deserialize, Serialize};
+use rkyv::api::high::{HighDeserializer, HighSerializer, HighValidator};
+use rkyv::bytecheck::CheckBytes;
+use rkyv::rancor::Error as RkyvError;
+use rkyv::ser::allocator::ArenaHandle;
+use rkyv::util::AlignedVec;
+use crate::state::auto_speed::{AutoSpeedSettings, EnterSpeedBehaviour};
+use crate::state::battle_view::BattleViewSettings;
+use crate::state::bookmark::BookmarkSettings;
+use crate::state::camera::{Freecam, FreecamFlags, NODE_PATH_DEFAULT_TIME};
+use crate::state::clip::ClipSettings;
+use crate::state::event_command::EvtCommandSettings;
+use crate::state::horizon::HorizonSettings;
+use crate::state::hud::HudSettings;
+use crate::state::interchange::InterchangeSettings;
+use crate::state::node::FreecamNode;
+use crate::state::pad::PadSettings;
+use crate::state::smoothing::SmoothingSettings;
+use crate::state::speed::SpeedSettings;
+use crate::state::timing::SPEED_RAMP_DEFAULT_TIME;
+
+const SESSION_FILE_NAME: &'static str = "debug_ui/session.p5session";
+// layout of the section container only. field changes go in the section versions below,
+// bumping this throws away the whole file
+const SESSION_VERSION: u32 = 1;
+// wait for changes to settle before writing to disk
+const SESSION_SAVE_DELAY: f32 = 1.;
+
+// bump a section's version when that field's type changes layout, only that field is reset on load
+type SessionSectionKey = (&'static str, u32);
+const SECTION_NODES: SessionSectionKey = ("nodes", 2);
+const SECTION_NODE_PATH_TIME: SessionSectionKey = ("node_path_time", 1);
+const SECTION_FOVY: SessionSectionKey = ("fovy", 1);
+const SECTION_GAME_SPEED: SessionSectionKey = ("game_speed", 1);
+const SECTION_LOCKED: SessionSectionKey = ("locked", 1);
+const SECTION_INTERCHANGE: SessionSectionKey = ("interchange", 1);
+const SECTION_WINDOW_RECT: SessionSectionKey = ("window_rect", 1);
+const SECTION_SMOOTHING: SessionSectionKey = ("smoothing", 1);
+const SECTION_BOOKMARKS: SessionSectionKey = ("bookmarks", 1);
+const SECTION_PAD: SessionSectionKey = ("pad", 1);
+const SECTION_QUATERNION_ORIENTATION: SessionSectionKey = ("quaternion_orientation", 1);
+const SECTION_HORIZON: SessionSectionKey = ("horizon", 1);
+const SECTION_SPEED: SessionSectionKey = ("speed", 1);
+const SECTION_CLIP: SessionSectionKey = ("clip", 1);
+const SECTION_GAME_SPEED_RAMP_TIME: SessionSectionKey = ("game_speed_ramp_time", 1);
+const SECTION_AUTO_SPEED: SessionSectionKey = ("auto_speed", 1);
+const SECTION_HUD: SessionSectionKey = ("hud", 1);
+const SECTION_EVT_COMMANDS: SessionSectionKey = ("evt_commands", 1);
+const SECTION_BATTLE_VIEW: SessionSectionKey = ("battle_view", 1);
+
+#[derive(Debug, Archive, Serialize, Deserialize)]
+struct SessionSection {
+    key: String,
+    version: u32,
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Default)]
+struct SessionWriter(Vec<SessionSection>);
+
+impl SessionWriter {
+    fn add<T>(&mut self, (key, version): SessionSectionKey, value: &T) -> Result<(), RkyvError>
+    where T: for<'a> Serialize<HighSerializer<AlignedVec, ArenaHandle<'a>, RkyvError>>
+    {
+        let data = rkyv::to_bytes::<RkyvError>(value)?.to_vec();
+        self.0.push(SessionSection { key: key.to_owned(), version, data });
+        Ok(())
+    }
+}
+
+struct SessionReader(Vec<SessionSection>);
+
+impl SessionReader {
+    // missing, outdated or unreadable sections fall back to the default
+    fn get<T>(&self, (key, version): SessionSectionKey) -> Option<T>
+    where T: Archive,
+          T::Archived: for<'a> CheckBytes<HighValidator<'a, RkyvError>> + Deserialize<T, HighDeserializer<RkyvError>>
+    {
+        let section = self.0.iter().find(|s| s.key == key)?;
+        if section.version != version {
+            logln!(Verbose, "Session {} was saved by a different version, resetting it", key);
+            return None;
+        }
+        let mut data: AlignedVec = AlignedVec::with_capacity(section.data.len());
+        data.extend_from_slice(&section.data);
+        match rkyv::from_bytes::<T, RkyvError>(&data) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                logln!(Verbose, "Error while parsing session {}: {}", key, e);
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FreecamSession {
+    pub(crate) nodes: Vec<FreecamNode>,
+    pub(crate) node_path_time: f32,
+    pub(crate) fovy: Option<f32>,
+    pub(crate) game_speed: Option<f32>,
+    pub(crate) locked: bool,
+    pub(crate) interchange: InterchangeSettings,
+    pub(crate) window_rect: Option<[i32; 4]>,
+    pub(crate) smoothing: SmoothingSettings,
+    pub(crate) bookmarks: BookmarkSettings,
+    pub(crate) pad: PadSettings,
+    pub(crate) quaternion_orientation: bool,
+    pub(crate) horizon: HorizonSettings,
+    pub(crate) speed: SpeedSettings,
+    pub(crate) clip: ClipSettings,
+    pub(crate) game_speed_ramp_time: f32,
+    pub(crate) auto_speed: AutoSpeedSettings,
+    pub(crate) hud: HudSettings,
+    pub(crate) evt_commands: EvtCommandSettings,
+    pub(crate) battle_view: BattleViewSettings,
+}
+
+impl FreecamSession {
+    fn get_path() -> PathBuf {
+        let mod_dir: String = mod_loader_data::get_directory_for_mod().into();
+        PathBuf::from(mod_dir).join(SESSION_FILE_NAME)
+    }
+
+    pub fn load() -> Option<Self> {
+        let buf = std::fs::read(Self::get_path()).ok()?;
+        if buf.len() < size_of::<u32>() || u32::from_le_bytes(buf[..size_of::<u32>()].try_into().unwrap()) != SESSION_VERSION {
+            logln!(Verbose, "Session file was saved by a different version, ignoring it");
+            return None;
+        }
+        let mut body: AlignedVec = AlignedVec::with_capacity(buf.len() - size_of::<u32>());
+        body.extend_from_slice(&buf[size_of::<u32>()..]);
+        let reader = match rkyv::from_bytes::<Vec<SessionSection>, RkyvError>(&body) {
+            Ok(v) => SessionReader(v),
+            Err(e) => {
+                logln!(Verbose, "Error while parsing session file: {}", e);
+                return None;
+            }
+        };
+        Some(Self {
+            nodes: reader.get(SECTION_NODES).unwrap_or_default(),
+            node_path_time: reader.get(SECTION_NODE_PATH_TIME).unwrap_or(NODE_PATH_DEFAULT_TIME),
+            fovy: reader.get(SECTION_FOVY).flatten(),
+            game_speed: reader.get(SECTION_GAME_SPEED).flatten(),
+            locked: reader.get(SECTION_LOCKED).unwrap_or_default(),
+            interchange: reader.get(SECTION_INTERCHANGE).unwrap_or_default(),
+            window_rect: reader.get(SECTION_WINDOW_RECT).flatten(),
+            smoothing: reader.get(SECTION_SMOOTHING).unwrap_or_default(),
+            bookmarks: reader.get(SECTION_BOOKMARKS).unwrap_or_default(),
+            pad: reader.get(SECTION_PAD).unwrap_or_default(),
+            quaternion_orientation: reader.get(SECTION_QUATERNION_ORIENTATION).unwrap_or_default(),
+            horizon: reader.get(SECTION_HORIZON).unwrap_or_default(),
+            speed: reader.get(SECTION_SPEED).unwrap_or_default(),
+            clip: reader.get(SECTION_CLIP).unwrap_or_default(),
+            game_speed_ramp_time: reader.get(SECTION_GAME_SPEED_RAMP_TIME).unwrap_or(SPEED_RAMP_DEFAULT_TIME),
+            auto_speed: reader.get(SECTION_AUTO_SPEED).unwrap_or_default(),
+            hud: reader.get(SECTION_HUD).unwrap_or_default(),
+            evt_commands: reader.get(SECTION_EVT_COMMANDS).unwrap_or_default(),
+            battle_view: reader.get(SECTION_BATTLE_VIEW).unwrap_or_default(),
+        })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let mut writer = SessionWriter::default();
+        writer.add(SECTION_NODES, &self.nodes)?;
+        writer.add(SECTION_NODE_PATH_TIME, &self.node_path_time)?;
+        writer.add(SECTION_FOVY, &self.fovy)?;
+        writer.add(SECTION_GAME_SPEED, &self.game_speed)?;
+        writer.add(SECTION_LOCKED, &self.locked)?;
+        writer.add(SECTION_INTERCHANGE, &self.interchange)?;
+        writer.add(SECTION_WINDOW_RECT, &self.window_rect)?;
+        writer.add(SECTION_SMOOTHING, &self.smoothing)?;
+        writer.add(SECTION_BOOKMARKS, &self.bookmarks)?;
+        writer.add(SECTION_PAD, &self.pad)?;
+        writer.add(SECTION_QUATERNION_ORIENTATION, &self.quaternion_orientation)?;
+        writer.add(SECTION_HORIZON, &self.horizon)?;
+        writer.add(SECTION_SPEED, &self.speed)?;
+        writer.add(SECTION_CLIP, &self.clip)?;
+        writer.add(SECTION_GAME_SPEED_RAMP_TIME, &self.game_speed_ramp_time)?;
+        writer.add(SECTION_AUTO_SPEED, &self.auto_speed)?;
+        writer.add(SECTION_HUD, &self.hud)?;
+        writer.add(SECTION_EVT_COMMANDS, &self.evt_commands)?;
+        writer.add(SECTION_BATTLE_VIEW, &self.battle_view)?;
+        let body = rkyv::to_bytes::<RkyvError>(&writer.0)?;
+        let mut buf = SESSION_VERSION.to_le_bytes().to_vec();
+        buf.extend_from_slice(body.as_slice());
+        std::fs::write(Self::get_path(), buf)?;
+        Ok(())
+    }
+}
+
+impl Freecam {
+    pub(crate) fn restore_session(&mut self) {
+        if let Some(session) = FreecamSession::load() {
+            logln!(Verbose, "Restored session ({} nodes, {:.02} sec)", session.nodes.len(), session.node_path_time);
+            self.nodes = session.nodes.clone();
+            self.node_path_time = session.node_path_time;
+            self.flags.set(FreecamFlags::LOCK_CAMERA_MOVEMENT, session.locked);
+            self.interchange = session.interchange.clone();
+            self.window_rect = session.window_rect;
+            self.smoothing.settings = session.smoothing.clone();
+            self.bookmarks = session.bookmarks.clone();
+            self.pad = session.pad.clone();
+            self.orientation.enabled = session.quaternion_orientation;
+            self.horizon = session.horizon.clone();
+            self.speed.settings = session.speed.clone();
+            self.clip = session.clip.clone();
+            self.time.ramp_time = session.game_speed_ramp_time;
+            self.auto_speed = session.auto_speed.clone();
+            self.hud.settings = session.hud.clone();
+            self.evt_commands.settings = session.evt_commands.clone();
+            self.battle_view.settings = session.battle_view.clone();
+            // camera settings can't be applied until the scene camera exists
+            self.pending_fovy = session.fovy;
+            self.pending_game_speed = session.game_speed;
+            self.saved_session = Some(session);
+        }
+    }
+
+    pub(crate) fn apply_pending_session(&mut self) {
+        if let Some(fovy) = self.pending_fovy.take() {
+            if let Some(cam) = Self::get_scene_camera_mut() { cam.set_fovy(fovy); }
+        }
+        // a frozen game isn't worth coming back to
+        if let Some(speed) = self.pending_game_speed.take().filter(|v| *v > 0.) {
+            // automatic game speed already decided what to use
+            if self.auto_speed.on_enter == EnterSpeedBehaviour::Keep && !self.auto_speed.remember_per_context {
+                Self::set_frequency_speed(speed);
+            }
+        }
+    }
+
+    fn create_session(&self) -> FreecamSession {
+        let prev = self.saved_session.as_ref();
+        // only remember camera settings that the user picked while in freecam
+        let active = self.flags.contains(FreecamFlags::ACTIVE);
+        let fovy = match active {
+            true => Self::get_scene_camera().map(|c| c.get_fovy()),
+            false => None
+        }.or(prev.and_then(|s| s.fovy));
+        let game_speed = match active {
+            true => Self::get_frequency_speed().filter(|v| *v > 0.),
+            false => None
+        }.or(prev.and_then(|s| s.game_speed));
+        let window_rect = match self.flags.contains(FreecamFlags::OPENED_DEBUG_WINDOW)
+            && !self.flags.contains(FreecamFlags::CLOSED_DEBUG_WINDOW) {
+            true => crate::gui::app::get_window_rect(),
+            false => None
+        }.or(self.window_rect);
+        FreecamSession {
+            nodes: self.nodes.clone(),
+            node_path_time: self.node_path_time,
+            fovy,
+            game_speed,
+            locked: self.flags.contains(FreecamFlags::LOCK_CAMERA_MOVEMENT),
+            interchange: self.interchange.clone(),
+            window_rect,
+            smoothing: self.smoothing.settings.clone(),
+            bookmarks: self.bookmarks.clone(),
+            pad: self.pad.clone(),
+            quaternion_orientation: self.orientation.enabled,
+            horizon: self.horizon.clone(),
+            speed: self.speed.settings.clone(),
+            clip: self.clip.clone(),
+            game_speed_ramp_time: self.time.ramp_time,
+            auto_speed: self.auto_speed.clone(),
+            hud: self.hud.settings.clone(),
+            evt_commands: self.evt_commands.settings.clone(),
+            battle_view: self.battle_view.settings.clone(),
+        }
+    }
+
+    pub(crate) fn save_session(&mut self) {
+        let session = self.create_session();
+        match session.save() {
+            Ok(_) => logln!(Verbose, "Saved session"),
+            Err(e) => logln!(Verbose, "Couldn't save session: {}", e),
+        }
+        self.saved_session = Some(session);
+        self.session_save_timer = 0.;
+        self.session_dirty = false;
+    }
+
+    // anything that changes saved state calls this, the save happens once changes stop
+    pub(crate) fn mark_session_dirty(&mut self) {
+        self.session_dirty = true;
+        self.session_save_timer = 0.;
+    }
+
+    pub(crate) fn update_session(&mut self, delta: f32) {
+        if crate::gui::app::take_window_moved() { self.mark_session_dirty(); }
+        if !self.session_dirty { return; }
+        self.session_save_timer += delta;
+        if self.session_save_timer >= SESSION_SAVE_DELAY {
+            self.save_session();
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use glam::{Vec3, Vec3A};
+use imgui::Ui;
+use rkyv::{Archive, Deserialize, Serialize};
+use crate::state::camera::Freecam;
+
+pub(crate) const SMOOTHING_MAX_TIME: f32 = 2.;
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct SmoothingSettings {
+    pub(crate) enabled: bool,
+    // approximate time in seconds to catch up with the target
+    pub(crate) translation_time: f32,
+    pub(crate) rotation_time: f32,
+}
+
+impl Default for SmoothingSettings {
+    fn default() -> Self {
+        Self { enabled: false, translation_time: 0.3, rotation_time: 0.15 }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FreecamSmoothing {
+    pub(crate) settings: SmoothingSettings,
+    target_pos: Vec3A,
+    // (pan, pitch, roll)
+    target_rot: Vec3,
+    pos_velocity: Vec3A,
+    rot_velocity: Vec3,
+    synced: bool,
+}
+
+// Critically damped spring, approximated as in Game Programming Gems 4 (1.10)
+pub(crate) fn smooth_damp<T>(current: T, target: T, velocity: &mut T, time: f32, delta: f32) -> T
+where T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<f32, Output = T>
+{
+    let omega = 2. / time.max(f32::EPSILON);
+    let x = omega * delta;
+    let exp = 1. / (1. + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + change * omega) * delta;
+    *velocity = (*velocity - temp * omega) * exp;
+    target + (change + temp) * exp
+}
+
+impl Freecam {
+    pub(crate) fn reset_smoothing(&mut self) {
+        self.smoothing.synced = false;
+        self.smoothing.pos_velocity = Vec3A::ZERO;
+        self.smoothing.rot_velocity = Vec3::ZERO;
+    }
+
+    fn sync_smoothing(&mut self) {
+        if !self.smoothing.synced {
+            self.smoothing.target_pos = self.camera_pos;
+            self.smoothing.target_rot = Vec3::new(self.pan, self.pitch, self.roll);
+            self.smoothing.synced = true;
+        }
+    }
+
+    pub(crate) fn apply_smoothed_translation(&mut self, movement: Vec3A, delta: f32) {
+        self.sync_smoothing();
+        let smooth = &mut self.smoothing;
+        smooth.target_pos += movement;
+        self.camera_pos = smooth_damp(self.camera_pos, smooth.target_pos,
+            &mut smooth.pos_velocity, smooth.settings.translation_time, delta);
+    }
+
+    // rot_delta is (pan, pitch, roll), move_delta is (right, up, back) relative to the camera
+    pub(crate) fn apply_manual_motion(&mut self, rot_delta: Vec3, move_delta: Vec3, delta: f32) {
+        if !self.smoothing.settings.enabled {
+            self.pan += rot_delta.x;
+            self.pitch = self.clamp_pitch(self.pitch + rot_delta.y);
+            self.roll += rot_delta.z;
+            self.camera_pos += Self::get_move_vector(self.pan, self.pitch, move_delta);
+            return;
+        }
+        self.sync_smoothing();
+        // inputs steer the target, the camera springs towards it
+        let pitch = self.clamp_pitch(self.smoothing.target_rot.y + rot_delta.y);
+        let smooth = &mut self.smoothing;
+        smooth.target_rot += rot_delta;
+        smooth.target_rot.y = pitch;
+        smooth.target_pos += Self::get_move_vector(smooth.target_rot.x, smooth.target_rot.y, move_delta);
+        let rot = smooth_damp(Vec3::new(self.pan, self.pitch, self.roll), smooth.target_rot,
+            &mut smooth.rot_velocity, smooth.settings.rotation_time, delta);
+        (self.pan, self.pitch, self.roll) = rot.into();
+        self.camera_pos = smooth_damp(self.camera_pos, smooth.target_pos,
+            &mut smooth.pos_velocity, smooth.settings.translation_time, delta);
+    }
+
+    pub(crate) fn draw_contents_smoothing(&mut self, ui: &Ui) {
+        if ui.checkbox("Inertia##ForFreecamWindow", &mut self.smoothing.settings.enabled) {
+            self.reset_smoothing();
+        }
+        ui.disabled(!self.smoothing.settings.enabled, || {
+            let width = ui.content_region_avail()[0] / 3.;
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(width);
+            ui.slider_config("Move##SmoothingForFreecamWindow", 0., SMOOTHING_MAX_TIME)
+                .display_format("%.2f sec").build(&mut self.smoothing.settings.translation_time);
+            ui.same_line_with_spacing(0., 10.);
+            ui.set_next_item_width(width);
+            ui.slider_config("Look##SmoothingForFreecamWindow", 0., SMOOTHING_MAX_TIME)
+                .display_format("%.2f sec").build(&mut self.smoothing.settings.rotation_time);
+        });
+    }
+}
@@ -0,0 +1,159 @@
+use imgui::{ItemHoveredFlags, SliderFlags, TreeNodeFlags, Ui};
+use riri_mod_tools_rt::logln;
+use rkyv::{Archive, Deserialize, Serialize};
+use windows::Win32::UI::Input::KeyboardAndMouse::VK_F5;
+use crate::state::camera::Freecam;
+use crate::state::pad::PadAction;
+
+pub(crate) const SPEED_MIN: f32 = 0.01;
+pub(crate) const SPEED_MAX: f32 = 10.;
+
+// multipliers on top of the base freecam speeds
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct SpeedPreset {
+    pub(crate) name: String,
+    pub(crate) move_speed: f32,
+    pub(crate) look_speed: f32,
+    pub(crate) roll_speed: f32,
+}
+
+impl SpeedPreset {
+    fn new(name: &str, move_speed: f32, look_speed: f32, roll_speed: f32) -> Self {
+        Self { name: name.to_owned(), move_speed, look_speed, roll_speed }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub struct SpeedSettings {
+    pub(crate) move_speed: f32,
+    pub(crate) look_speed: f32,
+    pub(crate) roll_speed: f32,
+    pub(crate) presets: Vec<SpeedPreset>,
+    pub(crate) current: Option<usize>,
+}
+
+impl Default for SpeedSettings {
+    fn default() -> Self {
+        Self {
+            move_speed: 1.,
+            look_speed: 1.,
+            roll_speed: 1.,
+            presets: vec![
+                SpeedPreset::new("Default", 1., 1., 1.),
+                SpeedPreset::new("Interior", 0.4, 0.7, 0.7),
+                SpeedPreset::new("Exterior", 2., 1., 1.),
+                SpeedPreset::new("Macro", 0.08, 0.35, 0.5),
+            ],
+            current: Some(0),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SpeedState {
+    pub(crate) settings: SpeedSettings,
+    new_preset_name: String,
+    pad_held: bool,
+}
+
+impl Freecam {
+    pub(crate) fn apply_speed_preset(&mut self, index: usize) {
+        let speed = &mut self.speed.settings;
+        if let Some(preset) = speed.presets.get(index) {
+            (speed.move_speed, speed.look_speed, speed.roll_speed) = (preset.move_speed, preset.look_speed, preset.roll_speed);
+            speed.current = Some(index);
+            logln!(Verbose, "Speed preset: {}", preset.name);
+        }
+    }
+
+    pub(crate) fn next_speed_preset(&mut self) {
+        let count = self.speed.settings.presets.len();
+        if count == 0 { return; }
+        let next = self.speed.settings.current.map_or(0, |i| (i + 1) % count);
+        self.apply_speed_preset(next);
+    }
+
+    // F5 or the pad's next preset binding cycles through presets
+    pub fn update_speed_preset(&mut self) {
+        let ctrl = unsafe { crate::globals::get_pad_instance().unwrap() };
+        let held = self.is_action_held(PadAction::NextPreset, ctrl.get_current().get_hold_press());
+        let pad_pressed = held && !self.speed.pad_held;
+        self.speed.pad_held = held;
+        if Self::check_key_pressed(VK_F5) || pad_pressed {
+            self.next_speed_preset();
+            self.mark_session_dirty();
+        }
+    }
+
+    pub(crate) fn draw_contents_speed(&mut self, ui: &Ui) {
+        if !ui.collapsing_header("Speed##ForFreecamWindow", TreeNodeFlags::empty()) { return; }
+        let max_width = ui.content_region_avail()[0];
+        let width = max_width / 5.;
+        let speed = &mut self.speed.settings;
+        let mut changed = false;
+        ui.set_next_item_width(width);
+        changed |= ui.slider_config("Move##ForFreecamSpeed", SPEED_MIN, SPEED_MAX)
+            .display_format("%.2fx").flags(SliderFlags::LOGARITHMIC).build(&mut speed.move_speed);
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        changed |= ui.slider_config("Look##ForFreecamSpeed", SPEED_MIN, SPEED_MAX)
+            .display_format("%.2fx").flags(SliderFlags::LOGARITHMIC).build(&mut speed.look_speed);
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        changed |= ui.slider_config("Roll##ForFreecamSpeed", SPEED_MIN, SPEED_MAX)
+            .display_format("%.2fx").flags(SliderFlags::LOGARITHMIC).build(&mut speed.roll_speed);
+        // values no longer match the preset they came from
+        if changed { speed.current = None; }
+
+        let names: Vec<String> = speed.presets.iter().map(|p| p.name.clone()).collect();
+        let preview = speed.current.and_then(|i| names.get(i).cloned()).unwrap_or("Custom".to_owned());
+        ui.set_next_item_width(width);
+        if let Some(_combo) = ui.begin_combo("Preset##ForFreecamSpeed", preview) {
+            for (i, name) in names.iter().enumerate() {
+                if ui.selectable_config(format!("{}##{}PresetForFreecamSpeed", name, i))
+                    .selected(self.speed.settings.current == Some(i)).build() {
+                    self.apply_speed_preset(i);
+                }
+            }
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(width);
+        ui.input_text("##NewPresetNameForFreecamSpeed", &mut self.speed.new_preset_name).hint("Preset name").build();
+        ui.same_line_with_spacing(0., 10.);
+        let speed = &mut self.speed.settings;
+        let name = match self.speed.new_preset_name.trim() {
+            "" => speed.current.and_then(|i| speed.presets.get(i)).map(|p| p.name.clone()),
+            v => Some(v.to_owned())
+        };
+        let existing = name.as_ref().and_then(|n| speed.presets.iter().position(|p| p.name == *n));
+        let label = match existing {
+            Some(_) => "Overwrite Preset##ForFreecamSpeed",
+            None => "Save Preset##ForFreecamSpeed"
+        };
+        let mut save = false;
+        ui.disabled(name.is_none(), || save = ui.button(label));
+        if ui.is_item_hovered_with_flags(ItemHoveredFlags::ALLOW_WHEN_DISABLED) {
+            match (&name, existing) {
+                (None, _) => ui.tooltip_text("Enter a name for the new preset"),
+                (Some(n), Some(_)) => ui.tooltip_text(format!("Replaces the speeds stored in \"{}\"", n)),
+                _ => ()
+            }
+        }
+        if let Some(name) = name.filter(|_| save) {
+            let preset = SpeedPreset::new(&name, speed.move_speed, speed.look_speed, speed.roll_speed);
+            // overwrite a preset with the same name, otherwise add a new one
+            match existing {
+                Some(i) => { speed.presets[i] = preset; speed.current = Some(i); },
+                None => { speed.presets.push(preset); speed.current = Some(speed.presets.len() - 1); }
+            }
+            self.speed.new_preset_name.clear();
+        }
+        ui.same_line_with_spacing(0., 10.);
+        let speed = &mut self.speed.settings;
+        ui.disabled(speed.current.is_none(), || {
+            if ui.button("Delete Preset##ForFreecamSpeed") {
+                if let Some(i) = speed.current.take() { speed.presets.remove(i); }
+            }
+        });
+    }
+}
@@ -0,0 +1,202 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Instant;
+use imgui::Ui;
+use riri_mod_tools_rt::logln;
+use windows::Win32::UI::Input::KeyboardAndMouse::{VK_OEM_PERIOD, VK_PAUSE};
+use opengfd::kernel::allocator::GfdAllocator;
+use opengfd::kernel::task::Task as GfdTask;
+use xrd744_lib::btl::{frequency::Frequency, package::Package};
+use crate::state::auto_speed::SpeedContext;
+use crate::state::camera::Freecam;
+
+// game speed used while stepping frames if nothing was running before the pause
+pub(crate) const STEP_DEFAULT_SPEED: f32 = 1.;
+pub(crate) const SPEED_RAMP_DEFAULT_TIME: f32 = 0.5;
+
+// game updates left to run before freezing again
+static STEP_REMAINING: AtomicU32 = AtomicU32::new(0);
+
+// called once per game update, true on the update that finishes the step
+pub(crate) fn tick_frame_step() -> bool {
+    STEP_REMAINING.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| v.checked_sub(1)) == Ok(1)
+}
+
+// battles ramp through the Frequency fade and only keep the target here.
+// the scene frequency has no fade of its own, so freecam runs the same from/to/time fade for it
+#[derive(Debug)]
+pub struct SpeedRamp {
+    from: f32,
+    to: f32,
+    duration: f32,
+    elapsed: f32,
+    // game speed may be zero, so ramps run on real time
+    last_update: Instant,
+}
+
+#[derive(Debug)]
+pub struct TimeControl {
+    // speed to go back to when unpausing
+    pub(crate) paused_speed: Option<f32>,
+    pub(crate) step_count: i32,
+    pub(crate) ramp_time: f32,
+    ramp: Option<SpeedRamp>,
+}
+
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self { paused_speed: None, step_count: 1, ramp_time: SPEED_RAMP_DEFAULT_TIME, ramp: None }
+    }
+}
+
+impl Freecam {
+    fn get_battle_frequency_mut() -> Option<&'static mut Frequency> {
+        GfdTask::<GfdAllocator, Package>::find_by_str_mut("battle")
+            .and_then(|btl| btl.get_main_work_mut().unwrap().get_frequency_mut())
+    }
+
+    pub(crate) fn get_ramp_target(&self) -> Option<f32> {
+        self.time.ramp.as_ref().map(|r| r.to)
+    }
+
+    // ease the game speed towards a new value instead of jumping to it
+    pub(crate) fn ramp_frequency_speed(&mut self, to: f32) {
+        let from = match Self::get_frequency_speed() {
+            Some(v) => v,
+            None => return
+        };
+        self.time.paused_speed = None;
+        STEP_REMAINING.store(0, Ordering::Relaxed);
+        match self.time.ramp_time > 0. && from != to {
+            true => {
+                if let Some(freq) = Self::get_battle_frequency_mut() { freq.start_fade(to, self.time.ramp_time); }
+                self.time.ramp = Some(SpeedRamp { from, to, duration: self.time.ramp_time, elapsed: 0., last_update: Instant::now() });
+            },
+            false => {
+                self.time.ramp = None;
+                Self::set_frequency_speed(to);
+            }
+        }
+    }
+
+    // runs every frame so a ramp started in freecam still finishes after leaving it
+    pub(crate) fn update_speed_ramp(&mut self) {
+        let ramp = match self.time.ramp.as_mut() {
+            Some(v) => v,
+            None => return
+        };
+        // the battle's own frequency update does the easing, wait for it to finish
+        if let Some(freq) = Self::get_battle_frequency_mut() {
+            if !freq.is_fading() { self.time.ramp = None; }
+            return;
+        }
+        let now = Instant::now();
+        ramp.elapsed += now.duration_since(ramp.last_update).as_secs_f32();
+        ramp.last_update = now;
+        let t = (ramp.elapsed / ramp.duration).min(1.);
+        Self::set_frequency_speed(ramp.from + (ramp.to - ramp.from) * t);
+        if t >= 1. { self.time.ramp = None; }
+    }
+
+    // finish a ramp or pause straight away, returning the speed the controls were heading for.
+    // a pause counts as 0, but the game is let go at the speed it was paused from
+    pub(crate) fn settle_time_control(&mut self) -> Option<f32> {
+        let target = match (self.get_ramp_target(), self.is_paused()) {
+            (Some(to), _) => Some(to),
+            (None, true) => Some(0.),
+            (None, false) => Self::get_frequency_speed()
+        };
+        let resume = self.time.paused_speed.or(self.get_ramp_target());
+        self.cancel_time_control();
+        if let Some(speed) = resume { Self::set_frequency_speed(speed); }
+        target
+    }
+
+    pub(crate) fn cancel_time_control(&mut self) {
+        self.time.paused_speed = None;
+        STEP_REMAINING.store(0, Ordering::Relaxed);
+        self.time.ramp = None;
+        if let Some(freq) = Self::get_battle_frequency_mut() { freq.stop_fade(); }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.time.paused_speed.is_some()
+    }
+
+    pub(crate) fn pause_game(&mut self) {
+        if self.is_paused() { return; }
+        let speed = Self::get_frequency_speed().unwrap_or(STEP_DEFAULT_SPEED);
+        self.time.paused_speed = Some(speed);
+        STEP_REMAINING.store(0, Ordering::Relaxed);
+        self.time.ramp = None;
+        Self::set_frequency_speed(0.);
+        logln!(Verbose, "Paused game (was {:.02}x)", speed);
+    }
+
+    pub(crate) fn resume_game(&mut self) {
+        if let Some(speed) = self.time.paused_speed.take() {
+            self.ramp_frequency_speed(speed);
+            logln!(Verbose, "Resumed game at {:.02}x", speed);
+        }
+    }
+
+    pub(crate) fn toggle_pause(&mut self) {
+        match self.is_paused() {
+            true => self.resume_game(),
+            false => self.pause_game()
+        }
+    }
+
+    // run the game for a number of updates at the speed it had before pausing, then freeze again
+    pub(crate) fn step_frames(&mut self, count: u32) {
+        if count == 0 { return; }
+        self.pause_game();
+        let speed = match self.time.paused_speed {
+            Some(v) if v > 0. => v,
+            _ => STEP_DEFAULT_SPEED
+        };
+        STEP_REMAINING.store(count, Ordering::Relaxed);
+        Self::set_frequency_speed(speed);
+    }
+
+    // called once per frame from the freecam task
+    pub fn update_time_control(&mut self) {
+        if Self::check_key_pressed(VK_PAUSE) {
+            self.toggle_pause();
+        }
+        if Self::check_key_pressed(VK_OEM_PERIOD) {
+            self.step_frames(self.time.step_count.max(1) as u32);
+        } else if Self::get_speed_context() == SpeedContext::Field && tick_frame_step() {
+            // the scene frequency has no update of its own, the field updates once per freecam tick.
+            // battles count from the frequency update hook in hooks/battle.rs
+            if self.is_paused() { Self::set_frequency_speed(0.); }
+        }
+        // speed was changed some other way while paused, so there's nothing to resume to
+        if STEP_REMAINING.load(Ordering::Relaxed) == 0 && self.is_paused() && Self::get_frequency_speed().map_or(false, |v| v > 0.) {
+            self.time.paused_speed = None;
+        }
+    }
+
+    pub(crate) fn draw_contents_time_control(&mut self, ui: &Ui) {
+        let pause = match self.is_paused() {
+            true => "Resume##ForFreecamTime", false => "Pause##ForFreecamTime"
+        };
+        if ui.button(pause) { self.toggle_pause(); }
+        ui.same_line_with_spacing(0., 10.);
+        if ui.button("Step##ForFreecamTime") {
+            self.step_frames(self.time.step_count.max(1) as u32);
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(80.);
+        if ui.input_int("Frames##ForFreecamTime", &mut self.time.step_count).build() {
+            self.time.step_count = self.time.step_count.max(1);
+        }
+        ui.same_line_with_spacing(0., 10.);
+        ui.set_next_item_width(80.);
+        if ui.input_float("Ramp##ForFreecamTime", &mut self.time.ramp_time).display_format("%.2f sec").build() {
+            self.time.ramp_time = self.time.ramp_time.max(0.);
+        }
+        if ui.is_item_hovered() {
+            ui.tooltip_text("Time taken to ease into a new game speed with the +/- keys");
+        }
+    }
+}
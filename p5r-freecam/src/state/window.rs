@@ -18,6 +18,20 @@ impl Freecam {
                 self.draw_contents_keyframes(ui);
                 ui.separator();
                 self.draw_contents_controls(ui);
+                ui.separator();
+                self.draw_contents_speed(ui);
+                self.draw_contents_auto_speed(ui);
+                self.draw_contents_orbit(ui);
+                self.draw_contents_bookmarks(ui);
+                self.draw_contents_hud(ui);
+                self.draw_contents_evt_commands(ui);
+                self.draw_contents_evt_trace(ui);
+                self.draw_contents_capture(ui);
+                self.draw_contents_battle_view(ui);
+                self.draw_contents_pad(ui);
+                self.draw_contents_interchange(ui);
+                // widgets edit settings in place, so treat any interaction as a change
+                if ui.is_any_item_active() { self.mark_session_dirty(); }
                 // add shortcuts if not already
                 /*
                 if self.shortcuts.is_empty() {
@@ -160,9 +160,13 @@ where A: Allocator + Clone
     pub fn get_current_time(&self) -> f32 { self.current_time }
     pub fn get_eye_pos(&self) -> Vec3A { self.eye_start }
     pub fn get_target_pos(&self) -> Vec3A { self.target_start }
+    pub fn get_current_near_clip(&self) -> f32 { self.current_near_clip }
+    pub fn get_current_far_clip(&self) -> f32 { self.current_far_clip }
 
     pub fn set_camera_flags(&mut self, value: CameraControlFlags) { self.flags = value }
     pub fn set_camera_type(&mut self, value: CameraType) { self.cam_type = value }
+    pub fn set_current_near_clip(&mut self, value: f32) { self.current_near_clip = value }
+    pub fn set_current_far_clip(&mut self, value: f32) { self.current_far_clip = value }
 
     pub fn get_camera(&self) -> &GfwCamera<A> { self.camera.get_data() }
     pub fn get_camera_mut(&mut self) -> &mut GfwCamera<A> { self.camera.get_data_mut() }
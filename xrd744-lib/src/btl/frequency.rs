@@ -18,4 +18,28 @@ pub struct Frequency {
 impl Frequency {
     pub fn get_time(&self) -> f32 { self.to_value }
     pub fn set_time(&mut self, time: f32) { self.to_value = time }
+
+    pub fn get_fade_state(&self) -> &FadeState { &self.fade_state }
+    pub fn get_time_elapsed(&self) -> f32 { self.time_elapsed }
+    pub fn get_target_time(&self) -> f32 { self.target_time }
+    pub fn get_from_value(&self) -> f32 { self.from_value }
+    pub fn get_target_value(&self) -> f32 { self.target_value }
+    pub fn is_fading(&self) -> bool { !matches!(self.fade_state, FadeState::None) }
+
+    // hand a speed change to the game's own update, which moves to_value from from_value
+    // to target_value over target_time seconds
+    pub fn start_fade(&mut self, to: f32, target_time: f32) {
+        self.from_value = self.to_value;
+        self.target_value = to;
+        self.change_value_by = to - self.from_value;
+        self.target_time = target_time;
+        self.target_time2 = target_time;
+        self.time_elapsed = 0.;
+        self.fade_state = FadeState::FadeIn;
+    }
+
+    pub fn stop_fade(&mut self) {
+        self.fade_state = FadeState::None;
+        self.time_elapsed = 0.;
+    }
 }
\ No newline at end of file
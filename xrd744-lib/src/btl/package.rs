@@ -15,6 +15,7 @@ use std::{
     ptr::NonNull
 };
 use crate::btl::frequency::Frequency;
+use crate::btl::unit::Unit;
 
 bitflags! {
     #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -252,8 +253,8 @@ where A: Allocator + Clone
     route: SmartPointer<usize, A>,
     camera_ctrl: SmartPointer<usize, A>,
     frequency: SmartPointer<Frequency, A>,
-    player_list: List<SmartPointer<usize, A>, A>,
-    enemy_list: List<SmartPointer<usize, A>, A>,
+    player_list: List<SmartPointer<Unit<A>, A>, A>,
+    enemy_list: List<SmartPointer<Unit<A>, A>, A>,
     action_list: List<SmartPointer<usize, A>, A>,
     voice_list: List<SmartPointer<usize, A>, A>,
     misc_units2: List<SmartPointer<usize, A>, A>,
@@ -437,4 +438,14 @@ where A: Allocator + Clone
     pub fn get_frequency_mut(&mut self) -> Option<&mut Frequency> {
         self.frequency.get_data_checked_mut()
     }
+    // field the battle was started from
+    pub fn get_field_id(&self) -> (u16, u16) {
+        (self.field_major, self.field_minor)
+    }
+    pub fn get_player_units(&self) -> impl Iterator<Item = &Unit<A>> {
+        self.player_list.iter().filter_map(|u| u.get_data_checked())
+    }
+    pub fn get_enemy_units(&self) -> impl Iterator<Item = &Unit<A>> {
+        self.enemy_list.iter().filter_map(|u| u.get_data_checked())
+    }
 }
\ No newline at end of file
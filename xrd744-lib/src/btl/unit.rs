@@ -0,0 +1,28 @@
+use std::ptr::NonNull;
+use allocator_api2::alloc::Allocator;
+use opengfd::{
+    kernel::allocator::GfdAllocator,
+    object::node::Node as GfdNode
+};
+
+// only the head of btl::Unit is mapped, enough to reach the model's root node
+#[repr(C)]
+pub struct Unit<A = GfdAllocator>
+where A: Allocator + Clone
+{
+    _cpp_vtable: *const u8,
+    resrc: *mut u8,
+    node: Option<NonNull<GfdNode<A>>>,
+    _allocator: A
+}
+
+impl<A> Unit<A>
+where A: Allocator + Clone
+{
+    pub fn get_node(&self) -> Option<&GfdNode<A>> {
+        self.node.map(|v| unsafe { v.as_ref() })
+    }
+    pub fn get_node_mut(&mut self) -> Option<&mut GfdNode<A>> {
+        self.node.map(|mut v| unsafe { v.as_mut() })
+    }
+}
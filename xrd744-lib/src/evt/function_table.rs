@@ -28,9 +28,13 @@ impl EvtCommandTable {
 #[repr(C)]
 #[derive(Debug)]
 pub struct EvtCommandExecuteTable {
+    // funcs[0] is the execute function, called with a pointer to the command's work data.
+    // that pointer hasn't been confirmed to point at the parameter block described by struct_size,
+    // so treat anything read through it as untrusted
     funcs: [*mut u8; 6],
     field30: u32,
     field34: u32,
+    // size in bytes of the command's parameter block as stored in the event file
     struct_size: u32,
     field3c: u32,
     field40: u32,
@@ -41,6 +45,9 @@ impl EvtCommandExecuteTable {
     pub fn get_func_0(&self) -> *mut u8 {
         self.funcs[0]
     }
+    pub fn get_struct_size(&self) -> u32 {
+        self.struct_size
+    }
 }
 
 #[repr(C)]
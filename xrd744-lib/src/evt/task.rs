@@ -44,6 +44,9 @@ where A: Allocator + Clone {
     pub fn get_ctrl_mut(&mut self) -> Option<&mut EvtCtrl<A>> {
         self.ctrl.map(|mut v| unsafe { v.as_mut() })
     }
+    pub fn get_current_frame(&self) -> i32 {
+        self.current_frame
+    }
 }
 
 #[repr(C)]
@@ -1,3 +1,8 @@
+use opengfd::kernel::{
+    allocator::GfdAllocator,
+    task::Task as GfdTask
+};
+
 #[repr(C)]
 pub struct MainParam {
     major: u16,
@@ -16,7 +21,27 @@ pub struct MainParam {
     field28: Option<fn()>
 }
 
+impl MainParam {
+    pub fn get_major(&self) -> u16 { self.major }
+    pub fn get_minor(&self) -> u16 { self.minor }
+    pub fn get_env_major(&self) -> u16 { self.env_major }
+    pub fn get_env_minor(&self) -> u16 { self.env_minor }
+}
+
 #[repr(C)]
 pub struct Main {
     magic: [u8; 4], // FMWK
-}
\ No newline at end of file
+    field04: u32,
+    param: MainParam,
+}
+
+impl Main {
+    pub fn get_current() -> Option<&'static Self> {
+        GfdTask::<GfdAllocator, Self>::find_by_str_mut("field main")
+            .and_then(|task| task.get_main_work())
+            .filter(|main| &main.magic == b"FMWK")
+    }
+    pub fn get_param(&self) -> &MainParam { &self.param }
+    // major/minor of the loaded field
+    pub fn get_field_id(&self) -> (u16, u16) { (self.param.major, self.param.minor) }
+}
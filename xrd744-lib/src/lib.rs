@@ -5,6 +5,7 @@ pub mod btl {
     pub mod camera;
     pub mod frequency;
     pub mod package;
+    pub mod unit;
 }
 pub mod cmp {
 